@@ -1,9 +1,6 @@
-use std::fs::File;
-use std::io::{prelude::*, BufReader};
+use std::io::prelude::*;
 
-fn elf_capacity_list(filename: &str) -> Result<Vec<i32>, std::io::Error> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
+fn elf_capacity_list(reader: impl BufRead) -> Result<Vec<i32>, std::io::Error> {
     let mut count = 0;
     let mut result = Vec::new();
     for line in reader.lines() {
@@ -19,7 +16,8 @@ fn elf_capacity_list(filename: &str) -> Result<Vec<i32>, std::io::Error> {
 }
 
 fn main() {
-    let mut v = elf_capacity_list("inputs/d1_1.txt").unwrap();
+    let input = aoc2022::load_input("d1");
+    let mut v = elf_capacity_list(input.as_bytes()).unwrap();
     v.sort_unstable();
     println!("ex1: {}", v.last().unwrap());
     let last3sum: i32 = (v[v.len() - 3..]).iter().sum();
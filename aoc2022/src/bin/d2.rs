@@ -1,6 +1,5 @@
-use std::fs::File;
 use std::io;
-use std::io::{prelude::*, BufReader};
+use std::io::prelude::*;
 
 #[derive(PartialEq, Copy, Clone)]
 enum Move {
@@ -89,9 +88,7 @@ fn single_match_score(elf_move: Move, player_move: Move) -> u32 {
     score + u32::from(player_move)
 }
 
-fn compute_score_ex1(filename: &str) -> io::Result<u32> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
+fn compute_score_ex1(reader: impl BufRead) -> io::Result<u32> {
     let mut total_score = 0;
     for line in reader.lines() {
         let move_strs: Vec<String> = line?.split_whitespace().map(str::to_string).collect();
@@ -102,9 +99,7 @@ fn compute_score_ex1(filename: &str) -> io::Result<u32> {
     Ok(total_score)
 }
 
-fn compute_score_ex2(filename: &str) -> io::Result<u32> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
+fn compute_score_ex2(reader: impl BufRead) -> io::Result<u32> {
     let mut total_score = 0;
     for line in reader.lines() {
         let move_strs: Vec<String> = line?.split_whitespace().map(str::to_string).collect();
@@ -116,6 +111,7 @@ fn compute_score_ex2(filename: &str) -> io::Result<u32> {
 }
 
 fn main() {
-    println!("ex1: {}", compute_score_ex1("inputs/d2_1.txt").unwrap());
-    println!("ex2: {}", compute_score_ex2("inputs/d2_1.txt").unwrap());
+    let input = aoc2022::load_input("d2");
+    println!("ex1: {}", compute_score_ex1(input.as_bytes()).unwrap());
+    println!("ex2: {}", compute_score_ex2(input.as_bytes()).unwrap());
 }
@@ -1,7 +1,6 @@
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
 use std::io;
-use std::io::{prelude::*, BufReader};
+use std::io::prelude::*;
 
 fn byte_to_priority(b: u8) -> u32 {
     match b {
@@ -11,9 +10,7 @@ fn byte_to_priority(b: u8) -> u32 {
     }
 }
 
-fn ex2(filename: &str) -> io::Result<u32> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
+fn ex2(reader: impl BufRead) -> io::Result<u32> {
     let mut result = 0;
     let mut hm: HashMap<u8, [bool; 3]> = HashMap::new();
     let mut common_char = 0;
@@ -41,9 +38,7 @@ fn ex2(filename: &str) -> io::Result<u32> {
     Ok(result)
 }
 
-fn ex1(filename: &str) -> io::Result<u32> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
+fn ex1(reader: impl BufRead) -> io::Result<u32> {
     let mut result = 0;
     for line in reader.lines() {
         let l = line?;
@@ -65,6 +60,7 @@ fn ex1(filename: &str) -> io::Result<u32> {
 }
 
 fn main() {
-    println!("ex1: {}", ex1("inputs/d3_1.txt").unwrap());
-    println!("ex2: {}", ex2("inputs/d3_1.txt").unwrap());
+    let input = aoc2022::load_input("d3");
+    println!("ex1: {}", ex1(input.as_bytes()).unwrap());
+    println!("ex2: {}", ex2(input.as_bytes()).unwrap());
 }
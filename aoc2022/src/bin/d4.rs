@@ -1,51 +1,36 @@
-use std::fs::File;
 use std::io;
-use std::io::{prelude::*, BufReader};
+use std::io::prelude::*;
 
-fn ex2(filename: &str) -> io::Result<u32> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
+use aoclib::interval::IntervalSet;
+
+fn parse_pair(line: &str) -> io::Result<(IntervalSet<u32>, IntervalSet<u32>)> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, format!("invalid pair {line}"));
+    let parse_range = |range: &str| -> io::Result<IntervalSet<u32>> {
+        let (start, end) = range.split_once('-').ok_or_else(invalid)?;
+        let start = start.parse::<u32>().map_err(|_| invalid())?;
+        let end = end.parse::<u32>().map_err(|_| invalid())?;
+        Ok([start..=end].into_iter().collect())
+    };
+    let (range1, range2) = line.split_once(',').ok_or_else(invalid)?;
+    Ok((parse_range(range1)?, parse_range(range2)?))
+}
+
+fn ex2(reader: impl BufRead) -> io::Result<u32> {
     let mut result = 0;
     for line in reader.lines() {
-        let range_str_pairs: Vec<String> = line?.split(',').map(str::to_string).collect();
-        let range1: Vec<u32> = range_str_pairs[0]
-            .split('-')
-            .map(str::parse::<u32>)
-            .filter_map(Result::ok)
-            .collect();
-        let range2: Vec<u32> = range_str_pairs[1]
-            .split('-')
-            .map(str::parse::<u32>)
-            .filter_map(Result::ok)
-            .collect();
-        if range1[0] <= range2[1] && range1[1] >= range2[0]
-            || range2[0] <= range1[1] && range2[1] >= range1[0]
-        {
+        let (range1, range2) = parse_pair(&line?)?;
+        if !range1.is_disjoint(&range2) {
             result += 1;
         }
     }
     Ok(result)
 }
 
-fn ex1(filename: &str) -> io::Result<u32> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
+fn ex1(reader: impl BufRead) -> io::Result<u32> {
     let mut result = 0;
     for line in reader.lines() {
-        let range_str_pairs: Vec<String> = line?.split(',').map(str::to_string).collect();
-        let range1: Vec<u32> = range_str_pairs[0]
-            .split('-')
-            .map(str::parse::<u32>)
-            .filter_map(Result::ok)
-            .collect();
-        let range2: Vec<u32> = range_str_pairs[1]
-            .split('-')
-            .map(str::parse::<u32>)
-            .filter_map(Result::ok)
-            .collect();
-        if range1[0] <= range2[0] && range1[1] >= range2[1]
-            || range2[0] <= range1[0] && range2[1] >= range1[1]
-        {
+        let (range1, range2) = parse_pair(&line?)?;
+        if range1.is_superset(&range2) || range2.is_superset(&range1) {
             result += 1;
         }
     }
@@ -53,6 +38,7 @@ fn ex1(filename: &str) -> io::Result<u32> {
 }
 
 fn main() {
-    println!("ex1: {}", ex1("inputs/d4_1.txt").unwrap());
-    println!("ex2: {}", ex2("inputs/d4_1.txt").unwrap());
+    let input = aoc2022::load_input("d4");
+    println!("ex1: {}", ex1(input.as_bytes()).unwrap());
+    println!("ex2: {}", ex2(input.as_bytes()).unwrap());
 }
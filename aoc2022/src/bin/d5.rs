@@ -1,7 +1,6 @@
 use std::collections::{BTreeSet, VecDeque};
-use std::fs::File;
 use std::io;
-use std::io::{prelude::*, BufReader};
+use std::io::prelude::*;
 
 fn parse_schema(lines: &[String], indexes: &BTreeSet<usize>) -> Vec<VecDeque<char>> {
     let mut res = vec![VecDeque::new(); indexes.len()];
@@ -49,9 +48,7 @@ fn apply_move(schema: &mut [VecDeque<char>], qty: usize, src: usize, dst: usize,
     }
 }
 
-fn ex(filename: &str, with_mud: bool) -> io::Result<usize> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
+fn ex(reader: impl BufRead, with_mud: bool) -> io::Result<usize> {
     let mut schema_strings = Vec::new();
     let mut schema: Option<Vec<VecDeque<char>>> = None;
     for line in reader.lines() {
@@ -84,6 +81,7 @@ fn ex(filename: &str, with_mud: bool) -> io::Result<usize> {
 }
 
 fn main() {
-    println!("ex1: {}", ex("inputs/d5_1.txt", false).unwrap());
-    println!("ex2: {}", ex("inputs/d5_1.txt", true).unwrap());
+    let input = aoc2022::load_input("d5");
+    println!("ex1: {}", ex(input.as_bytes(), false).unwrap());
+    println!("ex2: {}", ex(input.as_bytes(), true).unwrap());
 }
@@ -1,34 +1,32 @@
-use std::collections::HashSet;
-use std::fs::File;
 use std::io;
-use std::io::{prelude::*, BufReader};
+use std::io::prelude::*;
+use std::process;
 
-fn is_start_of_packet(arr: &[u8]) -> bool {
-    let mut s = HashSet::new();
-    for c in arr {
-        if s.contains(c) {
-            return false;
-        }
-        s.insert(c);
-    }
-    true
-}
+use aoclib::distinct_window::DistinctWindow;
 
-fn ex(filename: &str, size: usize) -> io::Result<usize> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
-    let mut rolling_window = vec![0u8; size];
+fn ex(reader: impl BufRead, size: usize) -> io::Result<usize> {
+    let mut window = DistinctWindow::new(size);
     // we just assume this is all ascii
     for (i, c) in reader.bytes().enumerate() {
-        rolling_window[i % size] = c?;
-        if i >= size-1 && is_start_of_packet(&rolling_window) {
+        if window.push(c?) {
             return Ok(i + 1);
         }
     }
-    Ok(0)
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("no marker of {size} distinct characters"),
+    ))
 }
 
 fn main() {
-    println!("ex1: {}", ex("inputs/d6_1.txt", 4).unwrap());
-    println!("ex2: {}", ex("inputs/d6_1.txt", 14).unwrap());
+    let input = aoc2022::load_input("d6");
+    for (part, size) in [("ex1", 4), ("ex2", 14)] {
+        match ex(input.as_bytes(), size) {
+            Ok(marker) => println!("{part}: {marker}"),
+            Err(err) => {
+                eprintln!("error: {err}");
+                process::exit(1);
+            }
+        }
+    }
 }
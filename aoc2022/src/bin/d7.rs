@@ -1,7 +1,6 @@
 use std::collections::{HashMap, hash_map::Entry};
-use std::fs::File;
 use std::io;
-use std::io::{prelude::*, BufReader};
+use std::io::prelude::*;
 
 #[derive(Debug)]
 enum FSEntry {
@@ -14,9 +13,7 @@ struct Dir {
     content: Vec<FSEntry>,
 }
 
-fn build_filesystem(filename: &str) -> io::Result<HashMap<Vec<String>, Dir>> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
+fn build_filesystem(reader: impl BufRead) -> io::Result<HashMap<Vec<String>, Dir>> {
     let mut current_dir: Vec<String> = Vec::new();
     let mut fs: HashMap<Vec<String>, Dir> = HashMap::new();
     fs.insert(vec!["/".to_string()], Dir {
@@ -113,7 +110,8 @@ fn ex2(fs: &HashMap<Vec<String>, Dir>) -> u32 {
 }
 
 fn main() {
-    let fs = build_filesystem("inputs/d7_1.txt").unwrap();
+    let input = aoc2022::load_input("d7");
+    let fs = build_filesystem(input.as_bytes()).unwrap();
     println!("ex1: {}", ex1(&fs));
     println!("ex2: {}", ex2(&fs));
 }
@@ -1,13 +1,10 @@
 use std::collections::HashSet;
-use std::fs::File;
 use std::io;
-use std::io::{prelude::*, BufReader};
+use std::io::prelude::*;
 
 static RADIX: u32 = 10;
 
-fn parse_map(filename: &str) -> io::Result<Vec<Vec<i8>>> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
+fn parse_map(reader: impl BufRead) -> io::Result<Vec<Vec<i8>>> {
 
     let mut res = Vec::new();
     for line in reader.lines() {
@@ -20,6 +17,7 @@ fn tree_index(i: usize, j: usize, map_dim: usize) -> usize {
     i*map_dim+j
 }
 
+#[allow(clippy::needless_range_loop)]
 fn compute_tree_scenic_score(map: &[Vec<i8>], tree_x: usize, tree_y: usize, map_dim: usize) -> usize {
     let visibility_lvl = map[tree_x][tree_y];
     let mut directions = [0; 4];
@@ -72,6 +70,7 @@ fn ex2(map: &[Vec<i8>]) -> usize {
     max_score
 }
 
+#[allow(clippy::needless_range_loop)]
 fn compute_outside_visibility(map: &[Vec<i8>]) -> usize {
     let mut visible_trees = HashSet::new();
     let map_dim = map.len();
@@ -124,7 +123,8 @@ fn ex1(map: &[Vec<i8>]) -> usize {
 }
 
 fn main() {
-    let map = parse_map("inputs/d8_1.txt").unwrap();
+    let input = aoc2022::load_input("d8");
+    let map = parse_map(input.as_bytes()).unwrap();
     println!("ex1: {}", ex1(&map));
     println!("ex2: {}", ex2(&map));
 }
\ No newline at end of file
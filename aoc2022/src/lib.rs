@@ -0,0 +1,5 @@
+/// Reads the input of `day` selected on the command line: nothing for `inputs/{day}_1.txt`,
+/// a variant name such as `_2` or `example`, a path, or `-` for stdin.
+pub fn load_input(day: &str) -> aoclib::input::Input {
+    aoclib::load_input(env!("CARGO_MANIFEST_DIR"), day)
+}
@@ -1,8 +1,9 @@
-use std::fs;
-use std::time::Instant;
+use aoc2023::run_day;
 
 use anyhow::{Error, Result};
 
+const DAY: &str = "d01";
+
 fn ex1(file: &str) -> Result<u32> {
     let mut sum = 0;
     for l in file.lines() {
@@ -99,24 +100,12 @@ fn num_at_pos(s: &str, pos: usize, c: char) -> Option<u32> {
 }
 
 fn main() {
-    let file = fs::read_to_string("./inputs/d01_1.txt").unwrap();
-
-    let start = Instant::now();
-    let res_ex1 = ex1(&file);
-    let duration = start.elapsed();
-    println!("ex1: {} (computed in {:?})", res_ex1.unwrap(), duration);
-
-    let start = Instant::now();
-    let res_ex2 = ex2(&file);
-    let duration = start.elapsed();
-    println!("ex2: {} (computed in {:?})", res_ex2.unwrap(), duration);
+    run_day(DAY, ex1, ex2);
 }
 
 #[cfg(test)]
 mod tests {
-    use std::fs;
-
-    use super::{ex1, ex2};
+    use super::{ex1, ex2, DAY};
 
     #[test]
     fn test_ex1() {
@@ -141,7 +130,7 @@ zoneight234
 
     #[test]
     fn test_file() {
-        let file = fs::read_to_string("./inputs/d01_1.txt").unwrap();
+        let file = aoc2023::read_input(DAY).unwrap();
         assert_eq!(54388, ex1(&file).unwrap());
         assert_eq!(53515, ex2(&file).unwrap());
     }
@@ -1,8 +1,9 @@
-use std::fs;
-use std::time::Instant;
+use aoc2023::run_day;
 
 use anyhow::{Error, Result};
 
+const DAY: &str = "d02";
+
 fn ex1(file: &str) -> Result<u32> {
     const MAX_RED: u32 = 12;
     const MAX_GREEN: u32 = 13;
@@ -111,24 +112,12 @@ fn ex2(file: &str) -> Result<u32> {
 }
 
 fn main() {
-    let file = fs::read_to_string("./inputs/d02_1.txt").unwrap();
-
-    let start = Instant::now();
-    let res_ex1 = ex1(&file);
-    let duration = start.elapsed();
-    println!("ex1: {} (computed in {:?})", res_ex1.unwrap(), duration);
-
-    let start = Instant::now();
-    let res_ex2 = ex2(&file);
-    let duration = start.elapsed();
-    println!("ex2: {} (computed in {:?})", res_ex2.unwrap(), duration);
+    run_day(DAY, ex1, ex2);
 }
 
 #[cfg(test)]
 mod tests {
-    use std::fs;
-
-    use super::{ex1, ex2};
+    use super::{ex1, ex2, DAY};
 
     #[test]
     fn test() {
@@ -143,7 +132,7 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
     #[test]
     fn test_file() {
-        let file = fs::read_to_string("./inputs/d02_1.txt").unwrap();
+        let file = aoc2023::read_input(DAY).unwrap();
         assert_eq!(2278, ex1(&file).unwrap());
         assert_eq!(67953, ex2(&file).unwrap());
     }
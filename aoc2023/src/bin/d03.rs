@@ -1,10 +1,12 @@
 use std::cmp::{max, min};
 use std::collections::{HashMap, HashSet};
-use std::fs;
-use std::time::Instant;
+
+use aoc2023::run_day;
 
 use anyhow::{Error, Result};
 
+const DAY: &str = "d03";
+
 fn has_neighbour_sym(arr: &Vec<&str>, x: usize, y: usize) -> bool {
     let min_x = max(x as isize - 1, 0);
     let min_y = max(y as isize - 1, 0);
@@ -123,24 +125,12 @@ fn ex2(file: &str) -> Result<u32> {
 }
 
 fn main() {
-    let file = fs::read_to_string("./inputs/d03_1.txt").unwrap();
-
-    let start = Instant::now();
-    let res_ex1 = ex1(&file);
-    let duration = start.elapsed();
-    println!("ex1: {} (computed in {:?})", res_ex1.unwrap(), duration);
-
-    let start = Instant::now();
-    let res_ex2 = ex2(&file);
-    let duration = start.elapsed();
-    println!("ex2: {} (computed in {:?})", res_ex2.unwrap(), duration);
+    run_day(DAY, ex1, ex2);
 }
 
 #[cfg(test)]
 mod tests {
-    use std::fs;
-
-    use super::{ex1, ex2};
+    use super::{ex1, ex2, DAY};
 
     #[test]
     fn test() {
@@ -160,7 +150,7 @@ mod tests {
 
     #[test]
     fn test_file() {
-        let file = fs::read_to_string("./inputs/d03_1.txt").unwrap();
+        let file = aoc2023::read_input(DAY).unwrap();
         assert_eq!(532428, ex1(&file).unwrap());
         assert_eq!(84051670, ex2(&file).unwrap());
     }
@@ -1,9 +1,11 @@
 use std::collections::HashSet;
-use std::fs;
-use std::time::Instant;
+
+use aoc2023::run_day;
 
 use anyhow::{Error, Result};
 
+const DAY: &str = "d04";
+
 fn count_line_winning_nums<'a>(l: &'a str, winning_nums_set: &mut HashSet<&'a str>) -> Result<u32> {
     let mut iter = l
         .split(": ")
@@ -62,17 +64,7 @@ fn ex2(file: &str) -> Result<u32> {
 }
 
 fn main() {
-    let file = fs::read_to_string("./inputs/d04_1.txt").unwrap();
-
-    let start = Instant::now();
-    let res_ex1 = ex1(&file);
-    let duration = start.elapsed();
-    println!("ex1: {} (computed in {:?})", res_ex1.unwrap(), duration);
-
-    let start = Instant::now();
-    let res_ex2 = ex2(&file);
-    let duration = start.elapsed();
-    println!("ex2: {} (computed in {:?})", res_ex2.unwrap(), duration);
+    run_day(DAY, ex1, ex2);
 }
 
 #[cfg(test)]
@@ -93,7 +85,7 @@ Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
 
     #[test]
     fn test_file() {
-        let file = fs::read_to_string("./inputs/d04_1.txt").unwrap();
+        let file = aoc2023::read_input(DAY).unwrap();
         assert_eq!(25571, ex1(&file).unwrap());
         assert_eq!(8805731, ex2(&file).unwrap());
     }
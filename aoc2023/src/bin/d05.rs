@@ -1,135 +1,71 @@
-use core::str::Lines;
-use std::fs;
-use std::time::Instant;
+use aoc2023::run_day;
+use aoclib::interval::IntervalSet;
+use aoclib::offset_map::OffsetMap;
 
 use anyhow::{Error, Result};
 use itertools::Itertools;
-use rangemap::RangeInclusiveMap;
-
-fn fill_rangemap(lines: &mut Lines<'_>) -> Result<RangeInclusiveMap<u64, u64>> {
-    let mut m = RangeInclusiveMap::new();
-    loop {
-        let l = lines.next();
-        if l.is_none() {
-            break;
-        }
-        let l = l.unwrap();
-        if l.is_empty() {
-            break;
-        }
-        let mut vals = l.split_whitespace();
-        let value = vals
-            .next()
-            .ok_or_else(|| Error::msg("bad map line"))?
-            .parse::<u64>()?;
-        let start = vals
-            .next()
-            .ok_or_else(|| Error::msg("bad map line"))?
-            .parse::<u64>()?;
-        let end = start
-            + vals
-                .next()
-                .ok_or_else(|| Error::msg("bad map line"))?
-                .parse::<u64>()?
-            - 1;
-        m.insert(start..=end, value)
-    }
-    Ok(m)
-}
 
-fn fill_maps(mut lines: Lines<'_>, maps: &mut [RangeInclusiveMap<u64, u64>; 7]) -> Result<()> {
-    let mut index = 0;
-    loop {
-        let l = lines.next();
-        if l.is_none() {
-            break;
+const DAY: &str = "d05";
+
+/// Parses the seeds line and composes every "destination source length" map of the almanac
+/// into a single seed to location map.
+fn parse_almanac(file: &str) -> Result<(Vec<u64>, OffsetMap<u64>)> {
+    let mut lines = file.lines();
+    let seeds = lines
+        .next()
+        .and_then(|l| l.strip_prefix("seeds:"))
+        .ok_or_else(|| Error::msg("missing seeds"))?
+        .split_whitespace()
+        .map(str::parse::<u64>)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut seed_to_location = OffsetMap::new();
+    let mut map = OffsetMap::new();
+    for l in lines {
+        if l.ends_with(':') {
+            seed_to_location = seed_to_location.then(&map);
+            map = OffsetMap::new();
+            continue;
         }
-        let l = l.unwrap();
         if l.is_empty() {
             continue;
         }
-        if l.ends_with(':') {
-            maps[index] = fill_rangemap(&mut lines)?;
-            index += 1;
-        }
+        let Some((dst, src, len)) = l.split_whitespace().map(str::parse::<u64>).collect_tuple()
+        else {
+            return Err(Error::msg("bad map line"));
+        };
+        let src = src?;
+        map.insert(src..src + len?, i128::from(dst?) - i128::from(src));
     }
-    Ok(())
+    Ok((seeds, seed_to_location.then(&map)))
 }
 
 fn ex1(file: &str) -> Result<u64> {
-    let mut lines = file.lines();
-    let seeds = lines.next().ok_or_else(|| Error::msg("empty file"))?[7..].split_whitespace();
-    let mut maps: [RangeInclusiveMap<u64, u64>; 7] = Default::default();
-    fill_maps(lines, &mut maps)?;
-
-    let mut min = u64::MAX;
-    for s in seeds {
-        let mut v = s.parse::<u64>()?;
-        for m in maps.iter() {
-            if let Some((k, val)) = m.get_key_value(&v) {
-                v = *val + (v - k.start());
-            }
-        }
-        if v < min {
-            min = v;
-        }
-    }
-    Ok(min)
+    let (seeds, seed_to_location) = parse_almanac(file)?;
+    seeds
+        .into_iter()
+        .map(|s| seed_to_location.apply(s))
+        .min()
+        .ok_or_else(|| Error::msg("no seeds"))
 }
 
 fn ex2(file: &str) -> Result<u64> {
-    let mut lines = file.lines();
-    let seeds = lines.next().ok_or_else(|| Error::msg("empty file"))?[7..].split_whitespace();
-    let mut maps: [RangeInclusiveMap<u64, u64>; 7] = Default::default();
-    fill_maps(lines, &mut maps)?;
-
-    let mut min = u64::MAX;
-    for (start, count) in seeds.tuples() {
-        let start = start.parse::<u64>()?;
-        let end = start + count.parse::<u64>()? - 1;
-        let mut seed = start;
-        while seed <= end {
-            let mut v = seed;
-            let mut smallest_intersection = u64::MAX;
-            for m in maps.iter() {
-                if let Some((k, val)) = m.get_key_value(&v) {
-                    let range_size = k.end() + 1 - k.start();
-                    if range_size < smallest_intersection {
-                        // find the smallest range containing seed
-                        // all next seed values until the end of that shortest range
-                        // will be the result for this seed value + 1, 2, 3 etc.
-                        // so they can be skipped
-                        smallest_intersection = k.end() + 1 - v;
-                    }
-                    v = *val + (v - k.start());
-                }
-            }
-            if v < min {
-                min = v;
-            }
-
-            seed += if smallest_intersection != u64::MAX {
-                smallest_intersection
-            } else {
-                1
-            }
-        }
-    }
-    Ok(min)
+    let (seeds, seed_to_location) = parse_almanac(file)?;
+    let seeds: IntervalSet<u64> = seeds
+        .into_iter()
+        .tuples()
+        .map(|(start, count)| start..start + count)
+        .collect();
+    seed_to_location
+        .image(&seeds)
+        .iter()
+        .next()
+        .map(|locations| *locations.start())
+        .ok_or_else(|| Error::msg("no seeds"))
 }
 
 fn main() {
-    let file = fs::read_to_string("./inputs/d05_1.txt").unwrap();
-
-    let start = Instant::now();
-    let res_ex1 = ex1(&file);
-    let duration = start.elapsed();
-    println!("ex1: {} (computed in {:?})", res_ex1.unwrap(), duration);
-
-    let start = Instant::now();
-    let res_ex2 = ex2(&file);
-    let duration = start.elapsed();
-    println!("ex2: {} (computed in {:?})", res_ex2.unwrap(), duration);
+    run_day(DAY, ex1, ex2);
 }
 
 #[cfg(test)]
@@ -176,7 +112,7 @@ humidity-to-location map:
 
     #[test]
     fn test_file() {
-        let file = fs::read_to_string("./inputs/d05_1.txt").unwrap();
+        let file = aoc2023::read_input(DAY).unwrap();
         assert_eq!(486613012, ex1(&file).unwrap());
         assert_eq!(56931769, ex2(&file).unwrap());
     }
@@ -1,9 +1,16 @@
-use std::fs;
-use std::time::Instant;
+use aoc2023::run_day;
+use aoclib::number_theory::product_window;
 
 use anyhow::{Error, Result};
 
-fn ex1(file: &str) -> Result<u64> {
+const DAY: &str = "d06";
+
+/// Number of ways to hold the button that beat the record `distance` in a race of `time`.
+fn ways_to_win(time: u128, distance: u128) -> u128 {
+    product_window(time, distance).map_or(0, |w| w.end() - w.start() + 1)
+}
+
+fn ex1(file: &str) -> Result<u128> {
     let mut lines = file.lines();
     let times = lines
         .next()
@@ -12,7 +19,7 @@ fn ex1(file: &str) -> Result<u64> {
         .nth(1)
         .ok_or_else(|| Error::msg("bad first line"))?
         .split_whitespace()
-        .map(str::parse::<u64>)
+        .map(str::parse::<u128>)
         .filter_map(Result::ok);
     let distances = lines
         .next()
@@ -21,19 +28,15 @@ fn ex1(file: &str) -> Result<u64> {
         .nth(1)
         .ok_or_else(|| Error::msg("bad second line"))?
         .split_whitespace()
-        .map(str::parse::<u64>)
+        .map(str::parse::<u128>)
         .filter_map(Result::ok);
     Ok(times
         .zip(distances)
-        .map(|(max_time, distance)| {
-            (1..max_time)
-                .filter(|t| (max_time - t) * t > distance)
-                .count() as u64
-        })
-        .product::<u64>())
+        .map(|(time, distance)| ways_to_win(time, distance))
+        .product())
 }
 
-fn ex2(file: &str) -> Result<u64> {
+fn ex2(file: &str) -> Result<u128> {
     let mut lines = file.lines();
     let time = lines
         .next()
@@ -42,7 +45,7 @@ fn ex2(file: &str) -> Result<u64> {
         .nth(1)
         .ok_or_else(|| Error::msg("bad first line"))?
         .replace(' ', "")
-        .parse::<f64>()?;
+        .parse::<u128>()?;
     let distance = lines
         .next()
         .ok_or_else(|| Error::msg("missing second line"))?
@@ -50,26 +53,12 @@ fn ex2(file: &str) -> Result<u64> {
         .nth(1)
         .ok_or_else(|| Error::msg("bad second line"))?
         .replace(' ', "")
-        .parse::<f64>()?;
-
-    let delta_sqrt = (time * time - 4. * distance).sqrt();
-    let x1 = ((time - delta_sqrt) / 2.).floor();
-    let x2 = ((time + delta_sqrt) / 2.).ceil();
-    Ok((x2 - x1) as u64 - 1) // (x2-1)-(x1+1) + 1
+        .parse::<u128>()?;
+    Ok(ways_to_win(time, distance))
 }
 
 fn main() {
-    let file = fs::read_to_string("./inputs/d06_1.txt").unwrap();
-
-    let start = Instant::now();
-    let res_ex1 = ex1(&file);
-    let duration = start.elapsed();
-    println!("ex1: {} (computed in {:?})", res_ex1.unwrap(), duration);
-
-    let start = Instant::now();
-    let res_ex2 = ex2(&file);
-    let duration = start.elapsed();
-    println!("ex2: {} (computed in {:?})", res_ex2.unwrap(), duration);
+    run_day(DAY, ex1, ex2);
 }
 
 #[cfg(test)]
@@ -85,7 +74,7 @@ Distance:  9  40  200";
 
     #[test]
     fn test_file() {
-        let file = fs::read_to_string("./inputs/d06_1.txt").unwrap();
+        let file = aoc2023::read_input(DAY).unwrap();
         assert_eq!(1413720, ex1(&file).unwrap());
         assert_eq!(30565288, ex2(&file).unwrap());
     }
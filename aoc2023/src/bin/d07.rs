@@ -1,172 +1,42 @@
-use std::cmp::Ordering;
-use std::collections::{BTreeMap, HashMap};
-use std::fs;
-use std::time::Instant;
+use aoc2023::run_day;
+use aoclib::cards::HandRules;
 
 use anyhow::{Error, Result};
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-enum HandType<const JOKER: bool> {
-    HighCard,
-    OnePair,
-    TwoPair,
-    ThreeOfAKind,
-    FullHouse,
-    FourOfAKind,
-    FiveOfAKind,
-}
-
-#[derive(Debug, PartialEq, Eq)]
-struct Hand<'a, const JOKER: bool> {
-    typ: HandType<JOKER>,
-    cards: &'a str,
-}
-
-impl<'a, const JOKER: bool> PartialOrd for Hand<'a, JOKER> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        if self.typ != other.typ {
-            return self.typ.partial_cmp(&other.typ);
-        }
-        for (c1, c2) in self.cards.chars().zip(other.cards.chars()) {
-            let mut cv1 = card_name_to_value(c1).unwrap();
-            let mut cv2 = card_name_to_value(c2).unwrap();
-            if JOKER {
-                cv1 = card_name_value_with_joker(cv1);
-                cv2 = card_name_value_with_joker(cv2);
-            }
-            match cv1.cmp(&cv2) {
-                Ordering::Greater => return Some(Ordering::Greater),
-                Ordering::Less => {
-                    return Some(Ordering::Less);
-                }
-                Ordering::Equal => {}
-            }
-        }
-        Some(Ordering::Equal)
-    }
-}
-
-impl<'a, const JOKER: bool> Ord for Hand<'a, JOKER> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
-    }
-}
-
-impl<const JOKER: bool> TryFrom<&str> for HandType<JOKER> {
-    type Error = Error;
-
-    fn try_from(s: &str) -> Result<Self> {
-        let mut hm: HashMap<char, u32> = HashMap::new();
-        let mut joker_count = 0;
-        s.chars().for_each(|c| {
-            if JOKER && c == 'J' {
-                joker_count += 1;
-            } else {
-                *hm.entry(c).or_default() += 1;
-            }
-        });
-        if joker_count >= 4 {
-            return Ok(HandType::FiveOfAKind);
-        }
-        match hm.len() {
-            1 => Ok(HandType::FiveOfAKind),
-            5 => Ok(HandType::HighCard),
-            _ => {
-                let mut lengths = hm.values().copied().collect::<Vec<_>>();
-                lengths.sort();
-                if let Some(v) = lengths.last_mut() {
-                    *v += joker_count;
-                };
-                match lengths[..] {
-                    [1, 4] => Ok(HandType::FourOfAKind),
-                    [2, 3] => Ok(HandType::FullHouse),
-                    [1, 1, 3] => Ok(HandType::ThreeOfAKind),
-                    [1, 2, 2] => Ok(HandType::TwoPair),
-                    [1, 1, 1, 2] => Ok(HandType::OnePair),
-                    _ => Err(Error::msg(format!(
-                        "error computing hand type: {} {:?}",
-                        s, lengths
-                    ))),
-                }
-            }
-        }
-    }
-}
-
-fn card_name_to_value(c: char) -> Result<u8> {
-    match c {
-        'T' => Ok(10),
-        'J' => Ok(11),
-        'Q' => Ok(12),
-        'K' => Ok(13),
-        'A' => Ok(14),
-        _ => {
-            let n = c
-                .to_digit(10)
-                .ok_or_else(|| Error::msg("invalid card name"))?;
-            match n {
-                2..=9 => Ok(n as u8),
-                _ => Err(Error::msg("invalid card name")),
-            }
-        }
-    }
-}
-
-fn card_name_value_with_joker(v: u8) -> u8 {
-    if v == 11 {
-        return 1;
-    }
-    v
+const DAY: &str = "d07";
+const CARDS: &str = "23456789TJQKA";
+
+/// Sum of the bids of the hands, each multiplied by its rank.
+fn total_winnings(file: &str, rules: &HandRules) -> Result<u64> {
+    let mut hands = file
+        .lines()
+        .map(|l| {
+            let (cards, bid) = l
+                .split_once(' ')
+                .ok_or_else(|| Error::msg(format!("invalid line: {l}")))?;
+            Ok((rules.key(cards)?, bid.trim().parse::<u64>()?))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    hands.sort_unstable();
+    Ok(hands
+        .iter()
+        .zip(1..)
+        .map(|((_, bid), rank)| bid * rank)
+        .sum())
 }
 
 fn ex1(file: &str) -> Result<u64> {
-    let mut m = BTreeMap::new();
-    for l in file.lines() {
-        let (hand_str, bid_str) = l.split_at(5);
-        m.insert(
-            Hand::<false> {
-                typ: hand_str.try_into()?,
-                cards: hand_str,
-            },
-            bid_str.trim().parse::<u64>()?,
-        );
-    }
-    Ok(m.values()
-        .enumerate()
-        .map(|(i, bid)| *bid * (i + 1) as u64)
-        .sum())
+    total_winnings(file, &HandRules::new(CARDS, 5)?)
 }
 
 fn ex2(file: &str) -> Result<u64> {
-    let mut m = BTreeMap::new();
-    for l in file.lines() {
-        let (hand_str, bid_str) = l.split_at(5);
-        m.insert(
-            Hand::<true> {
-                typ: hand_str.try_into()?,
-                cards: hand_str,
-            },
-            bid_str.trim().parse::<u64>()?,
-        );
-    }
-    Ok(m.values()
-        .enumerate()
-        .map(|(i, bid)| *bid * (i + 1) as u64)
-        .sum())
+    let mut rules = HandRules::new(CARDS, 5)?;
+    rules.wildcards("J")?;
+    total_winnings(file, &rules)
 }
 
 fn main() {
-    let file = fs::read_to_string("./inputs/d07_1.txt").unwrap();
-
-    let start = Instant::now();
-    let res_ex1 = ex1(&file);
-    let duration = start.elapsed();
-    println!("ex1: {} (computed in {:?})", res_ex1.unwrap(), duration);
-
-    let start = Instant::now();
-    let res_ex2 = ex2(&file);
-    let duration = start.elapsed();
-    println!("ex2: {} (computed in {:?})", res_ex2.unwrap(), duration);
+    run_day(DAY, ex1, ex2);
 }
 
 #[cfg(test)]
@@ -185,7 +55,7 @@ QQQJA 483";
 
     #[test]
     fn test_file() {
-        let file = fs::read_to_string("./inputs/d07_1.txt").unwrap();
+        let file = aoc2023::read_input(DAY).unwrap();
         assert_eq!(249726565, ex1(&file).unwrap());
         assert_eq!(251135960, ex2(&file).unwrap());
     }
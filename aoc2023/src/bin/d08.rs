@@ -1,9 +1,11 @@
 use std::collections::HashMap;
-use std::fs;
-use std::time::Instant;
+
+use aoc2023::run_day;
+use aoclib::cycle::{first_common_hit, Walk};
 
 use anyhow::{Error, Result};
-use num::integer::lcm;
+
+const DAY: &str = "d08";
 
 enum Instruction {
     Left,
@@ -22,14 +24,24 @@ impl TryFrom<char> for Instruction {
     }
 }
 
-fn ex1(file: &str) -> Result<u64> {
-    let mut lines = file.lines();
-    let instructions = lines
-        .next()
-        .ok_or_else(|| Error::msg("missing first line"))?
+fn parse_instructions(line: &str) -> Result<Vec<Instruction>> {
+    let instructions = line
         .chars()
         .map(|c| c.try_into())
         .collect::<Result<Vec<Instruction>>>()?;
+    if instructions.is_empty() {
+        return Err(Error::msg("no instructions"));
+    }
+    Ok(instructions)
+}
+
+fn ex1(file: &str) -> Result<u64> {
+    let mut lines = file.lines();
+    let instructions = parse_instructions(
+        lines
+            .next()
+            .ok_or_else(|| Error::msg("missing first line"))?,
+    )?;
 
     let mut cur: &str = Default::default();
 
@@ -64,12 +76,11 @@ fn ex1(file: &str) -> Result<u64> {
 
 fn ex2(file: &str) -> Result<u64> {
     let mut lines = file.lines();
-    let instructions = lines
-        .next()
-        .ok_or_else(|| Error::msg("missing first line"))?
-        .chars()
-        .map(|c| c.try_into())
-        .collect::<Result<Vec<Instruction>>>()?;
+    let instructions = parse_instructions(
+        lines
+            .next()
+            .ok_or_else(|| Error::msg("missing first line"))?,
+    )?;
 
     let mut cur_keys = Vec::new();
 
@@ -84,45 +95,35 @@ fn ex2(file: &str) -> Result<u64> {
         })
         .collect::<HashMap<_, _>>();
 
-    let mut counts = Vec::with_capacity(cur_keys.len());
-    for cur in cur_keys.iter_mut() {
-        for (i, instruction) in instructions.iter().cycle().enumerate() {
-            let pair = m
-                .get(cur)
-                .ok_or_else(|| Error::msg("missing entry from map"))?;
-            let next = if let Instruction::Left = instruction {
-                pair.0
-            } else {
-                pair.1
-            };
-            if next.ends_with('Z') {
-                counts.push(i as u64 + 1);
-                break;
-            }
-            *cur = next;
-        }
+    if m.values()
+        .any(|(l, r)| !m.contains_key(l) || !m.contains_key(r))
+    {
+        return Err(Error::msg("missing entry from map"));
     }
 
-    let mut it = counts.iter();
-    let mut least_common_mult = *it.next().ok_or_else(|| Error::msg("missing counts"))?;
-    for v in it {
-        least_common_mult = lcm(least_common_mult, *v);
-    }
-    Ok(least_common_mult)
+    // a ghost's state is its node and its position in the instructions
+    let walks = cur_keys
+        .iter()
+        .map(|&start| {
+            Walk::detect(
+                (start, 0),
+                |&(node, i)| {
+                    let pair = m[node];
+                    let next = match instructions[i] {
+                        Instruction::Left => pair.0,
+                        Instruction::Right => pair.1,
+                    };
+                    (next, (i + 1) % instructions.len())
+                },
+                |(node, _)| node.ends_with('Z'),
+            )
+        })
+        .collect::<Vec<_>>();
+    first_common_hit(&walks)?.ok_or_else(|| Error::msg("ghosts are never all on Z nodes"))
 }
 
 fn main() {
-    let file = fs::read_to_string("./inputs/d08_1.txt").unwrap();
-
-    let start = Instant::now();
-    let res_ex1 = ex1(&file);
-    let duration = start.elapsed();
-    println!("ex1: {} (computed in {:?})", res_ex1.unwrap(), duration);
-
-    let start = Instant::now();
-    let res_ex2 = ex2(&file);
-    let duration = start.elapsed();
-    println!("ex2: {} (computed in {:?})", res_ex2.unwrap(), duration);
+    run_day(DAY, ex1, ex2);
 }
 
 #[cfg(test)]
@@ -160,11 +161,23 @@ ZZZ = (ZZZ, ZZZ)";
 22Z = (22B, 22B)
 XXX = (XXX, XXX)";
         assert_eq!(6, ex2(input).unwrap());
+        // the first Z hits are 2 and 1 steps in, but the ghosts only meet after 5 steps
+        let input = "L
+
+11A = (11B, 11B)
+11B = (11Z, 11Z)
+11Z = (11C, 11C)
+11C = (11B, 11B)
+22A = (22Z, 22Z)
+22Z = (22B, 22B)
+22B = (22Z, 22Z)";
+        assert_eq!(5, ex2(input).unwrap());
+        assert!(ex2("\n\n11A = (11Z, 11Z)\n11Z = (11Z, 11Z)").is_err());
     }
 
     #[test]
     fn test_file() {
-        let file = fs::read_to_string("./inputs/d08_1.txt").unwrap();
+        let file = aoc2023::read_input(DAY).unwrap();
         assert_eq!(12643, ex1(&file).unwrap());
         assert_eq!(13133452426987, ex2(&file).unwrap());
     }
@@ -1,60 +1,47 @@
-use std::fs;
-use std::num::ParseIntError;
-use std::time::Instant;
+use std::io::BufRead;
+
+use aoc2023::run_day_streaming;
+use aoclib::sequence::{kth_term, kth_term_i128};
 
 use anyhow::{Error, Result};
 
-fn sum_extrapolations(file: &str, reverse: bool) -> Result<i64> {
-    let mut sum = 0;
-    for l in file.lines() {
-        let mut numbers = l
+const DAY: &str = "d09";
+
+/// Sum over the lines of the term of index `index(len)` of the line's sequence of `len` terms,
+/// in `i128` unless a term needs big integers.
+fn sum_extrapolations(input: &mut dyn BufRead, index: fn(usize) -> i64) -> Result<i64> {
+    let mut sum = 0i128;
+    for l in input.lines() {
+        let l = l?;
+        let numbers = l
             .split_whitespace()
             .map(str::parse::<i64>)
-            .collect::<std::result::Result<Vec<i64>, ParseIntError>>()?;
-        if reverse {
-            numbers.reverse();
-        }
-        let last = *numbers.last().ok_or_else(|| Error::msg("vec is empty"))?;
-        let mut all_zero = false;
-        let mut sum_last_elements = 0;
-        while !all_zero {
-            all_zero = true;
-            let mut diff = 0;
-            for i in 0..numbers.len() - 1 {
-                diff = numbers[i + 1] - numbers[i];
-                if diff != 0 {
-                    all_zero = false;
-                }
-                numbers[i] = diff;
+            .collect::<Result<Vec<i64>, _>>()?;
+        let k = index(numbers.len());
+        let term = match kth_term_i128(&numbers, k) {
+            Some(term) => term,
+            None => {
+                let term = kth_term(&numbers, k).ok_or_else(|| Error::msg("vec is empty"))?;
+                i128::try_from(term).map_err(|_| Error::msg("term overflows i128"))?
             }
-            numbers.remove(numbers.len() - 1);
-            sum_last_elements += diff;
-        }
-        sum += last + sum_last_elements;
+        };
+        sum = sum
+            .checked_add(term)
+            .ok_or_else(|| Error::msg("sum overflows i128"))?;
     }
-    Ok(sum)
+    i64::try_from(sum).map_err(|_| Error::msg("sum overflows i64"))
 }
 
-fn ex1(file: &str) -> Result<i64> {
-    sum_extrapolations(file, false)
+fn ex1(input: &mut dyn BufRead) -> Result<i64> {
+    sum_extrapolations(input, |len| len as i64)
 }
 
-fn ex2(file: &str) -> Result<i64> {
-    sum_extrapolations(file, true)
+fn ex2(input: &mut dyn BufRead) -> Result<i64> {
+    sum_extrapolations(input, |_| -1)
 }
 
 fn main() {
-    let file = fs::read_to_string("./inputs/d09_1.txt").unwrap();
-
-    let start = Instant::now();
-    let res_ex1 = ex1(&file);
-    let duration = start.elapsed();
-    println!("ex1: {} (computed in {:?})", res_ex1.unwrap(), duration);
-
-    let start = Instant::now();
-    let res_ex2 = ex2(&file);
-    let duration = start.elapsed();
-    println!("ex2: {} (computed in {:?})", res_ex2.unwrap(), duration);
+    run_day_streaming(DAY, ex1, ex2);
 }
 
 #[cfg(test)]
@@ -65,14 +52,14 @@ mod tests {
         let input = "0 3 6 9 12 15
 1 3 6 10 15 21
 10 13 16 21 30 45";
-        assert_eq!(114, ex1(input).unwrap());
-        assert_eq!(2, ex2(input).unwrap());
+        assert_eq!(114, ex1(&mut input.as_bytes()).unwrap());
+        assert_eq!(2, ex2(&mut input.as_bytes()).unwrap());
     }
 
     #[test]
     fn test_file() {
-        let file = fs::read_to_string("./inputs/d09_1.txt").unwrap();
-        assert_eq!(1806615041, ex1(&file).unwrap());
-        assert_eq!(1211, ex2(&file).unwrap());
+        let file = aoc2023::read_input(DAY).unwrap();
+        assert_eq!(1806615041, ex1(&mut file.as_bytes()).unwrap());
+        assert_eq!(1211, ex2(&mut file.as_bytes()).unwrap());
     }
 }
@@ -1,8 +1,9 @@
-use std::fs;
-use std::time::Instant;
+use aoc2023::run_day;
 
 use anyhow::{Error, Result};
 
+const DAY: &str = "d10";
+
 #[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
 enum Cardinal {
     #[default]
@@ -134,7 +135,7 @@ impl Map {
         }
     }
 
-    fn iter(&self) -> PathIterator {
+    fn iter(&self) -> PathIterator<'_> {
         PathIterator {
             map: self,
             cur: self.start_pos,
@@ -243,17 +244,7 @@ fn ex2(file: &str) -> Result<usize> {
 }
 
 fn main() {
-    let file = fs::read_to_string("./inputs/d10_1.txt").unwrap();
-
-    let start = Instant::now();
-    let res_ex1 = ex1(&file);
-    let duration = start.elapsed();
-    println!("ex1: {} (computed in {:?})", res_ex1.unwrap(), duration);
-
-    let start = Instant::now();
-    let res_ex2 = ex2(&file);
-    let duration = start.elapsed();
-    println!("ex2: {} (computed in {:?})", res_ex2.unwrap(), duration);
+    run_day(DAY, ex1, ex2);
 }
 
 #[cfg(test)]
@@ -313,7 +304,7 @@ L7JLJL-JLJLJL--JLJ.L";
 
     #[test]
     fn test_file() {
-        let file = fs::read_to_string("./inputs/d10_1.txt").unwrap();
+        let file = aoc2023::read_input(DAY).unwrap();
         assert_eq!(6951, ex1(&file).unwrap());
         assert_eq!(563, ex2(&file).unwrap());
     }
@@ -1,8 +1,9 @@
-use std::fs;
-use std::time::Instant;
+use aoc2023::run_day;
 
 use anyhow::{Error, Result};
 
+const DAY: &str = "d11";
+
 fn distance(g1: (i64, i64), g2: (i64, i64)) -> i64 {
     (g2.1 - g1.1).abs() + (g2.0 - g1.0).abs()
 }
@@ -63,17 +64,7 @@ fn ex2(file: &str, scale: i64) -> Result<i64> {
 }
 
 fn main() {
-    let file = fs::read_to_string("./inputs/d11_1.txt").unwrap();
-
-    let start = Instant::now();
-    let res_ex1 = ex1(&file);
-    let duration = start.elapsed();
-    println!("ex1: {} (computed in {:?})", res_ex1.unwrap(), duration);
-
-    let start = Instant::now();
-    let res_ex2 = ex2(&file, 1000000);
-    let duration = start.elapsed();
-    println!("ex2: {} (computed in {:?})", res_ex2.unwrap(), duration);
+    run_day(DAY, ex1, |file| ex2(file, 1000000));
 }
 
 #[cfg(test)]
@@ -98,7 +89,7 @@ mod tests {
 
     #[test]
     fn test_file() {
-        let file = fs::read_to_string("./inputs/d11_1.txt").unwrap();
+        let file = aoc2023::read_input(DAY).unwrap();
         assert_eq!(9445168, ex1(&file).unwrap());
         assert_eq!(742305960572, ex2(&file, 1000000).unwrap());
     }
@@ -0,0 +1,35 @@
+use std::fmt::Display;
+use std::io::BufRead;
+
+use anyhow::Result;
+use aoclib::input::{self, Input, InputSource};
+
+/// Runs both parts of `day` on every `inputs/{day}_*.txt` and checks them against
+/// `answers.txt`, or only on the input selected on the command line: a variant name such as
+/// `_2` or `example`, a path, or `-` for stdin.
+pub fn run_day<F1, F2, R1, R2>(day: &str, ex1: F1, ex2: F2)
+where
+    F1: Fn(&str) -> Result<R1>,
+    F2: Fn(&str) -> Result<R2>,
+    R1: Display,
+    R2: Display,
+{
+    aoclib::run_day(env!("CARGO_MANIFEST_DIR"), day, ex1, ex2)
+}
+
+/// Same as [`run_day`] for days reading their input as a stream, line by line, instead of
+/// as a whole string.
+pub fn run_day_streaming<F1, F2, R1, R2>(day: &str, ex1: F1, ex2: F2)
+where
+    F1: Fn(&mut dyn BufRead) -> Result<R1>,
+    F2: Fn(&mut dyn BufRead) -> Result<R2>,
+    R1: Display,
+    R2: Display,
+{
+    aoclib::run_day_streaming(env!("CARGO_MANIFEST_DIR"), day, ex1, ex2)
+}
+
+/// Reads `inputs/{day}_1.txt`, or its encrypted `.txt.enc` version.
+pub fn read_input(day: &str) -> Result<Input> {
+    input::read_input(env!("CARGO_MANIFEST_DIR"), day, &InputSource::default())
+}
@@ -55,7 +55,6 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::DAY;
-    use std::fs;
 
     use super::{ex1, ex2};
 
@@ -76,8 +75,7 @@ mod tests {
 
     #[test]
     fn test_file() {
-        let file =
-            fs::read_to_string(format!("./inputs/{DAY}_1.txt")).expect("failed to read input file");
+        let file = aoc2024::read_input(DAY).expect("failed to read input file");
         let expected_ex1: i64 = 765748;
         let expected_ex2: i64 = 27732508;
         assert_eq!(expected_ex1, ex1(&file).expect("ex1 failed"));
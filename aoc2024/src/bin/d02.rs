@@ -1,18 +1,20 @@
 use std::cmp::Ordering;
+use std::io::BufRead;
 
-use aoc2024::run_day;
+use aoc2024::run_day_streaming;
 
 use anyhow::{Context, Result};
 use itertools::Itertools;
 
 const DAY: &str = "d02";
 
-fn ex1(file: &str) -> Result<i64> {
+fn ex1(input: &mut dyn BufRead) -> Result<i64> {
     let mut num_safe = 0;
 
-    let filtered_iter = file
+    let filtered_iter = input
         .lines()
         .map(|l| -> Result<bool> {
+            let l = l?;
             let mut split = l.split_ascii_whitespace();
             let mut prev_num = split
                 .next()
@@ -41,10 +43,10 @@ fn ex1(file: &str) -> Result<i64> {
     Ok(num_safe)
 }
 
-fn ex2(file: &str) -> Result<i64> {
+fn ex2(input: &mut dyn BufRead) -> Result<i64> {
     let mut num_safe = 0;
-    for l in file.lines() {
-        let levels = l
+    for l in input.lines() {
+        let levels = l?
             .split_ascii_whitespace()
             .map(|s| s.parse::<i64>().map_err(Into::into))
             .collect::<Result<Vec<_>>>()?;
@@ -84,13 +86,12 @@ fn ex2(file: &str) -> Result<i64> {
 }
 
 fn main() {
-    run_day(DAY, ex1, ex2);
+    run_day_streaming(DAY, ex1, ex2);
 }
 
 #[cfg(test)]
 mod tests {
     use super::DAY;
-    use std::fs;
 
     use super::{ex1, ex2};
 
@@ -104,17 +105,22 @@ mod tests {
 1 3 6 7 9";
         let expected_ex1: i64 = 2;
         let expected_ex2: i64 = 4; // FIXME (output from example)
-        assert_eq!(expected_ex1, ex1(example).expect("ex1 failed"));
-        assert_eq!(expected_ex2, ex2(example).expect("ex2 failed"));
+        assert_eq!(
+            expected_ex1,
+            ex1(&mut example.as_bytes()).expect("ex1 failed")
+        );
+        assert_eq!(
+            expected_ex2,
+            ex2(&mut example.as_bytes()).expect("ex2 failed")
+        );
     }
 
     #[test]
     fn test_file() {
-        let file =
-            fs::read_to_string(format!("./inputs/{DAY}_1.txt")).expect("failed to read input file");
+        let file = aoc2024::read_input(DAY).expect("failed to read input file");
         let expected_ex1: i64 = 371;
         let expected_ex2: i64 = 426; // FIXME (output computed when ex1 passed check)
-        assert_eq!(expected_ex1, ex1(&file).expect("ex1 failed"));
-        assert_eq!(expected_ex2, ex2(&file).expect("ex2 failed"));
+        assert_eq!(expected_ex1, ex1(&mut file.as_bytes()).expect("ex1 failed"));
+        assert_eq!(expected_ex2, ex2(&mut file.as_bytes()).expect("ex2 failed"));
     }
 }
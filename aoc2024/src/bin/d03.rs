@@ -1,35 +1,49 @@
 use aoc2024::run_day;
+use aoclib::cli_flag;
+use aoclib::interpreter::Interpreter;
 
 use anyhow::Result;
-use regex::Regex;
+use itertools::Itertools;
 
 const DAY: &str = "d03";
 
+#[derive(Default)]
+struct State {
+    sum: u64,
+    disabled: bool,
+}
+
+/// Sum of the products of the `mul` instructions of the program, those after a `don't()` and
+/// before the next `do()` being skipped when `conditionals` is set. Prints the offset of each
+/// instruction with the `--trace` flag.
+fn run_program(file: &str, conditionals: bool) -> Result<i64> {
+    let trace = cli_flag("trace");
+    let mut interpreter = Interpreter::new();
+    interpreter.register("mul", 2, |s: &mut State, args| {
+        if !s.disabled {
+            s.sum += args[0] * args[1];
+        }
+    })?;
+    if conditionals {
+        interpreter
+            .register("do", 0, |s, _| s.disabled = false)?
+            .register("don't", 0, |s, _| s.disabled = true)?;
+    }
+    let mut state = State::default();
+    interpreter.run_traced(file.as_bytes(), &mut state, |i| {
+        if trace {
+            eprintln!("{:>8} {}({})", i.offset, i.name, i.args.iter().join(","));
+        }
+    })?;
+    Ok(i64::try_from(state.sum)?)
+}
+
 fn ex1(file: &str) -> Result<i64> {
-    Regex::new(r"mul\(([0-9]+),([0-9]+)\)")?
-        .captures_iter(file)
-        .map(|c| c.extract())
-        .map(|(_, [arg1, arg2])| Ok(arg1.parse::<i64>()? * arg2.parse::<i64>()?))
-        .sum()
+    run_program(file, false)
 }
 
 fn ex2(file: &str) -> Result<i64> {
-    Regex::new(r"(?:mul\(([0-9]+),([0-9]+)\))|(?:(do)\(()\))|(?:(don't)\(()\))")?
-        .captures_iter(file)
-        .map(|c| c.extract())
-        .try_fold((0, true), |(prev_val, do_action), cap| match cap.1 {
-            ["do", _] => Ok((prev_val, true)),
-            ["don't", _] => Ok((prev_val, false)),
-            [arg1, arg2] => Ok((
-                (if do_action {
-                    prev_val + arg1.parse::<i64>()? * arg2.parse::<i64>()?
-                } else {
-                    prev_val
-                }),
-                do_action,
-            )),
-        })
-        .map(|(r, _)| r)
+    run_program(file, true)
 }
 
 fn main() {
@@ -39,7 +53,6 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::DAY;
-    use std::fs;
 
     use super::{ex1, ex2};
 
@@ -55,8 +68,7 @@ mod tests {
 
     #[test]
     fn test_file() {
-        let file =
-            fs::read_to_string(format!("./inputs/{DAY}_1.txt")).expect("failed to read input file");
+        let file = aoc2024::read_input(DAY).expect("failed to read input file");
         let expected_ex1: i64 = 163931492;
         let expected_ex2: i64 = 76911921;
         assert_eq!(expected_ex1, ex1(&file).expect("ex1 failed"));
@@ -87,7 +87,6 @@ fn ex2(file: &str) -> Result<i64> {
             .filter(|r| matches!(r, Err(_) | Ok(true))),
         |r| r.count() as i64,
     )
-    .map_err(Into::into)
 }
 
 fn main() {
@@ -97,7 +96,6 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::DAY;
-    use std::fs;
 
     use super::{ex1, ex2};
 
@@ -133,8 +131,7 @@ M.M.M.M.M.
 
     #[test]
     fn test_file() {
-        let file =
-            fs::read_to_string(format!("./inputs/{DAY}_1.txt")).expect("failed to read input file");
+        let file = aoc2024::read_input(DAY).expect("failed to read input file");
         let expected_ex1: i64 = 2593;
         let expected_ex2: i64 = 1950;
         assert_eq!(expected_ex1, ex1(&file).expect("ex1 failed"));
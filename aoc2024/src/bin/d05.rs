@@ -1,121 +1,70 @@
-use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
-
 use aoc2024::run_day;
+use aoclib::partial_order::Precedence;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use itertools::Itertools;
 
 const DAY: &str = "d05";
 
-struct RuleChecker(HashMap<i64, (HashSet<i64>, HashSet<i64>)>);
-
-impl RuleChecker {
-    fn new() -> Self {
-        RuleChecker(HashMap::new())
-    }
-
-    fn compare(&self, a: i64, b: i64) -> Ordering {
-        match self.0.get(&a) {
-            Some((less_vals, _)) if less_vals.contains(&b) => Ordering::Greater,
-            Some((_, greater_vals)) if greater_vals.contains(&b) => Ordering::Less,
-            _ => Ordering::Equal,
-        }
-    }
-
-    fn validate_update(&self, upd: &Update) -> i64 {
-        for (pos, page) in upd.values.iter().enumerate() {
-            let Some((less_values, greater_values)) = self.0.get(page) else {
-                continue;
-            };
-            for less_val in less_values {
-                if let Some(true) = upd.page_pos.get(less_val).map(|other| *other > pos) {
-                    return 0;
-                }
-            }
-            for greater_val in greater_values {
-                if let Some(true) = upd.page_pos.get(greater_val).map(|other| *other < pos) {
-                    return 0;
-                }
-            }
-        }
-        upd.middle_value
-    }
-}
-
-struct Update {
-    middle_value: i64,
-    values: Vec<i64>,
-    page_pos: HashMap<i64, usize>,
-}
-
-fn parse(file: &str) -> Result<(RuleChecker, Vec<Update>)> {
+fn parse(file: &str) -> Result<(Precedence<i64>, Vec<Vec<i64>>)> {
     let mut sections_iter = file.split("\n\n");
 
-    let mut checker = RuleChecker::new();
-    sections_iter
+    let mut rules = Precedence::new();
+    for l in sections_iter
         .next()
         .context("missing rules section")?
         .lines()
-        .map(|l| {
-            l.split('|')
-                .next_tuple()
-                .context("invalid rule line")
-                .map(|(a, b)| Ok::<_, anyhow::Error>((a.parse::<i64>()?, b.parse::<i64>()?)))?
-        })
-        .try_for_each(|r| {
-            let (low, high) = r?;
-            checker.0.entry(low).or_default().1.insert(high);
-            checker.0.entry(high).or_default().0.insert(low);
-            Ok::<(), anyhow::Error>(())
-        })?;
+    {
+        let (before, after) = l.split('|').next_tuple().context("invalid rule line")?;
+        rules.add_rule(before.parse::<i64>()?, after.parse::<i64>()?);
+    }
 
     let updates = sections_iter
         .next()
         .context("missing updates section")?
         .lines()
         .map(|l| {
-            l.split(',')
-                .map(&str::parse::<i64>)
-                .enumerate()
-                .map(|(pos, val)| {
-                    let val = val?;
-                    Ok(((val), (val, pos)))
-                })
-                .collect::<Result<(Vec<_>, HashMap<_, _>)>>()
-        })
-        .map(|r| {
-            let (v, hm) = r?;
-            Ok(Update {
-                middle_value: *(v.get(v.len() / 2).context("failed to get middle value")?),
-                values: v,
-                page_pos: hm,
-            })
+            let update = l
+                .split(',')
+                .map(str::parse::<i64>)
+                .collect::<Result<Vec<_>, _>>()?;
+            if update.is_empty() {
+                bail!("empty update");
+            }
+            Ok(update)
         })
-        .collect::<Result<Vec<Update>>>()?;
-    Ok((checker, updates))
+        .collect::<Result<Vec<_>>>()?;
+    Ok((rules, updates))
 }
 
 fn ex1(file: &str) -> Result<i64> {
-    let (checker, updates) = parse(file)?;
+    let (rules, updates) = parse(file)?;
 
     Ok(updates
-        .into_iter()
-        .map(|upd| checker.validate_update(&upd))
+        .iter()
+        .filter(|upd| rules.violation(upd).is_none())
+        .map(|upd| upd[upd.len() / 2])
         .sum())
 }
 
 fn ex2(file: &str) -> Result<i64> {
-    let (checker, updates) = parse(file)?;
-
-    Ok(updates
-        .into_iter()
-        .filter(|upd| checker.validate_update(upd) == 0)
-        .map(|mut upd| {
-            upd.values.sort_by(|a, b| checker.compare(*a, *b));
-            upd.values[upd.values.len() / 2]
+    let (rules, updates) = parse(file)?;
+
+    updates
+        .iter()
+        .filter(|upd| rules.violation(upd).is_some())
+        .map(|upd| {
+            let Some(sorted) = rules.linearize(upd) else {
+                let cycle = rules.cycle(upd).unwrap_or_default();
+                bail!("rules for update {upd:?} form a cycle: {cycle:?}");
+            };
+            let middle = sorted.order.len() / 2;
+            if !sorted.unique && !rules.is_fixed(&sorted.order, middle) {
+                bail!("update {upd:?} has several correct orders with different middles");
+            }
+            Ok(sorted.order[middle])
         })
-        .sum())
+        .sum()
 }
 
 fn main() {
@@ -125,7 +74,6 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::DAY;
-    use std::fs;
 
     use super::{ex1, ex2};
 
@@ -165,10 +113,19 @@ mod tests {
         assert_eq!(expected_ex2, ex2(example).expect("ex2 failed"));
     }
 
+    #[test]
+    fn test_several_orders() {
+        // 1 and 2 come before 3 in either order, so the middle of 3,1,2 is ambiguous while
+        // the middle of 5,3,1,2,4 is always 3
+        let ambiguous = "1|3\n2|3\n\n3,1,2";
+        assert!(ex2(ambiguous).is_err());
+        let fixed = "1|3\n2|3\n3|4\n3|5\n\n5,3,1,2,4";
+        assert_eq!(3, ex2(fixed).expect("ex2 failed"));
+    }
+
     #[test]
     fn test_file() {
-        let file =
-            fs::read_to_string(format!("./inputs/{DAY}_1.txt")).expect("failed to read input file");
+        let file = aoc2024::read_input(DAY).expect("failed to read input file");
         let expected_ex1: i64 = 5588;
         let expected_ex2: i64 = 5331;
         assert_eq!(expected_ex1, ex1(&file).expect("ex1 failed"));
@@ -21,7 +21,6 @@
 // #[cfg(test)]
 // mod tests {
 //     use super::DAY;
-//     use std::fs;
 
 //     use super::{ex1, ex2};
 
@@ -36,8 +35,7 @@
 
 //     // #[test]
 //     // fn test_file() {
-//     //     let file =
-//     //         fs::read_to_string(format!("./inputs/{DAY}_1.txt")).expect("failed to read input file");
+//     //     let file = aoc2024::read_input(DAY).expect("failed to read input file");
 //     //     let expected_ex1: i64 = 0; // FIXME (output computed when ex1 passed check)
 //     //     let expected_ex2: i64 = 0; // FIXME (output computed when ex1 passed check)
 //     //     assert_eq!(expected_ex1, ex1(&file).expect("ex1 failed"));
@@ -1,20 +1,32 @@
 use aoc2025::run_day;
 
 use anyhow::{Context, Result};
-use bounded_integer::BoundedI32;
 
 const DAY: &str = "d01";
 
+const DIAL_SIZE: i64 = 100;
+
+/// Signed rotations, left being negative.
+fn rotations(file: &str) -> impl Iterator<Item = Result<i64>> + '_ {
+    file.lines().map(|l| {
+        let rot_num: i64 = l
+            .get(1..)
+            .context("empty rotation")?
+            .parse()
+            .with_context(|| format!("failed to parse rotation {l:?}"))?;
+        Ok(if l.starts_with('L') {
+            -rot_num
+        } else {
+            rot_num
+        })
+    })
+}
+
 fn ex1(file: &str) -> Result<i64> {
-    let mut dial = <BoundedI32<0, 99>>::new(50).context("failed to init bounded int")?;
+    let mut dial = 50;
     let mut count_zeroes = 0;
-    for l in file.lines() {
-        let rot_num: i32 = l[1..].parse()?;
-        if let Some('L') = l.chars().next() {
-            dial = dial.wrapping_sub(rot_num);
-        } else {
-            dial = dial.wrapping_add(rot_num);
-        }
+    for rotation in rotations(file) {
+        dial = (dial + rotation?).rem_euclid(DIAL_SIZE);
         if dial == 0 {
             count_zeroes += 1;
         }
@@ -23,24 +35,17 @@ fn ex1(file: &str) -> Result<i64> {
 }
 
 fn ex2(file: &str) -> Result<i64> {
-    let mut dial = <BoundedI32<0, 99>>::new(50).context("failed to init bounded int")?;
-    let mut count_zeroes: i64 = 0;
-    for l in file.lines() {
-        let rot_num: i32 = l[1..].parse()?;
-        if let Some('L') = l.chars().next() {
-            let sub = dial.get() - rot_num;
-            let base = if dial == 0 { 0 } else { 1 };
-            if sub <= 0 {
-                count_zeroes += (base + sub.abs() / 100) as i64;
-            }
-            dial = dial.wrapping_sub(rot_num);
+    let mut dial = 50;
+    let mut count_zeroes = 0;
+    for rotation in rotations(file) {
+        let target = dial + rotation?;
+        // multiples of the dial size in (dial, target] going right, [target, dial) going left
+        count_zeroes += if target > dial {
+            target.div_euclid(DIAL_SIZE) - dial.div_euclid(DIAL_SIZE)
         } else {
-            let add = dial.get() + rot_num;
-            if add > 99 {
-                count_zeroes += (add / 100) as i64;
-            }
-            dial = dial.wrapping_add(rot_num);
-        }
+            (dial - 1).div_euclid(DIAL_SIZE) - (target - 1).div_euclid(DIAL_SIZE)
+        };
+        dial = target.rem_euclid(DIAL_SIZE);
     }
     Ok(count_zeroes)
 }
@@ -52,7 +57,6 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::DAY;
-    use std::fs;
 
     use super::{ex1, ex2};
 
@@ -77,8 +81,7 @@ L82
 
     #[test]
     fn test_file() {
-        let file =
-            fs::read_to_string(format!("./inputs/{DAY}_1.txt")).expect("failed to read input file");
+        let file = aoc2025::read_input(DAY).expect("failed to read input file");
         let expected_ex1: i64 = 1152;
         let expected_ex2: i64 = 6671;
         assert_eq!(expected_ex1, ex1(&file).expect("ex1 failed"));
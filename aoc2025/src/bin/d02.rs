@@ -1,55 +1,75 @@
 use aoc2025::run_day;
+use aoclib::number_theory::{divisors, mobius};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 
 const DAY: &str = "d02";
 
-fn ex1(file: &str) -> Result<i64> {
-    Ok(file
-        .trim_ascii()
+fn parse_ranges(file: &str) -> Result<Vec<(u64, u64)>> {
+    file.trim_ascii()
         .split(',')
-        .filter_map(|rang| rang.split_once('-'))
-        .filter_map(|(low, high)| Some((low.parse::<i64>().ok()?, high.parse::<i64>().ok()?)))
-        .map(|(low, high)| {
-            (low..=high)
-                .filter(|&id| {
-                    let s = id.to_string();
-                    s[..s.len() / 2] == s[s.len() / 2..]
-                })
-                .sum::<i64>()
+        .map(|rang| {
+            let (low, high) = rang.split_once('-').context("failed to parse range")?;
+            Ok((low.parse()?, high.parse()?))
         })
-        .sum())
+        .collect()
 }
 
-fn ex2(file: &str) -> Result<i64> {
-    Ok(file
-        .trim_ascii()
-        .split(',')
-        .filter_map(|rang| rang.split_once('-'))
-        .filter_map(|(low, high)| Some((low.parse::<i64>().ok()?, high.parse::<i64>().ok()?)))
-        .map(|(low, high)| {
-            (low..=high)
-                .filter(|&id| {
-                    let s = id.to_string();
-                    for slicelen in (1..=s.len() / 2).filter(|len| s.len() % len == 0) {
-                        let mut ok = true;
-                        for k in 0..s.len() / slicelen - 1 {
-                            if s[k * slicelen..(k + 1) * slicelen]
-                                != s[(k + 1) * slicelen..(k + 2) * slicelen]
-                            {
-                                ok = false;
-                                break;
-                            }
-                        }
-                        if ok {
-                            return true;
-                        }
-                    }
-                    false
-                })
-                .sum::<i64>()
+fn num_digits(n: u64) -> u32 {
+    n.checked_ilog10().unwrap_or(0) + 1
+}
+
+/// Sum of the IDs of `low..=high` made of a block of `block_len` digits repeated to fill
+/// `len` digits. Those are the multiples of (10^len - 1) / (10^block_len - 1) by a block of
+/// exactly `block_len` digits, so the sum is an arithmetic series.
+fn sum_repeated(low: u64, high: u64, len: u32, block_len: u32) -> i128 {
+    let step = (10u128.pow(len) - 1) / (10u128.pow(block_len) - 1);
+    let first = (low as u128).div_ceil(step).max(10u128.pow(block_len - 1));
+    let last = (high as u128 / step).min(10u128.pow(block_len) - 1);
+    if first > last {
+        return 0;
+    }
+    (step * (first + last) * (last - first + 1) / 2) as i128
+}
+
+/// Sum of the IDs of the ranges made of a repeated block, `repetitions` giving for each number
+/// of digits the block lengths to count, with their weights.
+fn sum_invalid(ranges: &[(u64, u64)], repetitions: impl Fn(u32) -> Vec<(u32, i128)>) -> i128 {
+    ranges
+        .iter()
+        .flat_map(|&(low, high)| {
+            (num_digits(low)..=num_digits(high)).map(move |len| (low, high, len))
+        })
+        .flat_map(|(low, high, len)| {
+            repetitions(len)
+                .into_iter()
+                .map(move |(block_len, sign)| sign * sum_repeated(low, high, len, block_len))
         })
-        .sum())
+        .sum()
+}
+
+fn ex1(file: &str) -> Result<i64> {
+    let ranges = parse_ranges(file)?;
+    let sum = sum_invalid(&ranges, |len| match len % 2 {
+        0 => vec![(len / 2, 1)],
+        _ => Vec::new(),
+    });
+    Ok(i64::try_from(sum)?)
+}
+
+fn ex2(file: &str) -> Result<i64> {
+    let ranges = parse_ranges(file)?;
+    // an ID repeating blocks of two lengths repeats blocks of their gcd, so the union over the
+    // block lengths is an inclusion-exclusion weighted by the Möbius function
+    let sum = sum_invalid(&ranges, |len| {
+        divisors(len as u64)
+            .into_iter()
+            .filter(|&block_len| block_len < len as u64)
+            .map(|block_len| (block_len as u32, -mobius(len as u64 / block_len) as i128))
+            .filter(|&(_, sign)| sign != 0)
+            .collect()
+    });
+    Ok(i64::try_from(sum)?)
 }
 
 fn main() {
@@ -59,9 +79,30 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::DAY;
-    use std::fs;
 
-    use super::{ex1, ex2};
+    use super::{ex1, ex2, parse_ranges};
+
+    /// The IDs of the ranges whose digits are a block repeated at least twice, and exactly
+    /// twice if `halves`, by checking every one of them.
+    fn brute_force(file: &str, halves: bool) -> i64 {
+        let ranges = parse_ranges(file).expect("failed to parse ranges");
+        let invalid = |id: u64| {
+            let s = id.to_string();
+            (1..s.len())
+                .filter(|&block| s.len().is_multiple_of(block) && (!halves || block * 2 == s.len()))
+                .any(|block| {
+                    s.as_bytes()
+                        .chunks(block)
+                        .all(|c| c == &s.as_bytes()[..block])
+                })
+        };
+        ranges
+            .iter()
+            .flat_map(|&(low, high)| low..=high)
+            .filter(|&id| invalid(id))
+            .map(|id| id as i64)
+            .sum()
+    }
 
     #[test]
     fn test() {
@@ -70,12 +111,15 @@ mod tests {
         let expected_ex2: i64 = 4174379265;
         assert_eq!(expected_ex1, ex1(example).expect("ex1 failed"));
         assert_eq!(expected_ex2, ex2(example).expect("ex2 failed"));
+
+        let ranges = "1-1000000,1188511880-1188511890,99999999-100000010";
+        assert_eq!(brute_force(ranges, true), ex1(ranges).expect("ex1 failed"));
+        assert_eq!(brute_force(ranges, false), ex2(ranges).expect("ex2 failed"));
     }
 
     #[test]
     fn test_file() {
-        let file =
-            fs::read_to_string(format!("./inputs/{DAY}_1.txt")).expect("failed to read input file");
+        let file = aoc2025::read_input(DAY).expect("failed to read input file");
         let expected_ex1: i64 = 9188031749;
         let expected_ex2: i64 = 11323661261;
         assert_eq!(expected_ex1, ex1(&file).expect("ex1 failed"));
@@ -1,62 +1,54 @@
 use aoc2025::run_day;
+use aoclib::subsequence::largest_number;
 
-use anyhow::Result;
-use iter_first_max::IterFirstMaxExt;
+use anyhow::{Context, Result, bail};
+use num_bigint::BigUint;
 
 const DAY: &str = "d03";
 
-fn ex1(file: &str) -> Result<i64> {
-    let s = file
-        .lines()
-        .filter_map(|l| {
-            let len = l.len();
-            let batteries_iter = l.chars().filter_map(|c| c.to_digit(10));
-            let max = batteries_iter
-                .clone()
-                .take(len - 1)
-                .enumerate()
-                .first_max_by_key(|(_idx, val)| *val)?;
-            let second_max = batteries_iter.skip(max.0 + 1).max()?;
-            format!("{}{}", max.1, second_max).parse::<i64>().ok()
+/// Batteries turned on per bank in part 2, unless overridden with `--batteries=N`.
+const PART2_BATTERIES: usize = 12;
+
+/// Sum over the banks of the largest joltage made by turning on `batteries` of them.
+fn total_joltage(file: &str, batteries: usize) -> Result<BigUint> {
+    file.lines()
+        .map(|l| {
+            let digits = l
+                .bytes()
+                .map(|b| match b {
+                    b'0'..=b'9' => Ok(b - b'0'),
+                    _ => bail!("invalid battery {:?} in bank {l:?}", b as char),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let joltage = largest_number(&digits, batteries)
+                .with_context(|| format!("bank {l:?} has fewer than {batteries} batteries"))?;
+            Ok(joltage.to_biguint())
         })
-        .sum::<i64>();
-    Ok(s)
+        .sum()
+}
+
+fn ex1(file: &str) -> Result<i64> {
+    Ok(i64::try_from(total_joltage(file, 2)?)?)
 }
 
 fn ex2(file: &str) -> Result<i64> {
-    let s = file
-        .lines()
-        .filter_map(|l| {
-            let len = l.len();
-            let batteries_iter = l.chars().filter_map(|c| c.to_digit(10));
-            let mut max_ind = 0;
-            let mut chars: [u8; 12] = [0; 12];
-            for ind in 1..=12 {
-                let max = batteries_iter
-                    .clone()
-                    .enumerate()
-                    .take(len - (12 - ind))
-                    .skip(if ind == 1 { 0 } else { max_ind + 1 })
-                    .first_max_by_key(|(_idx, val)| *val)?;
-                max_ind = max.0;
-                chars[ind - 1] = char::from_digit(max.1, 10)? as u8;
-            }
-            str::from_utf8(&chars).ok()?.parse::<i64>().ok()
-        })
-        .sum::<i64>();
-    Ok(s)
+    Ok(i64::try_from(total_joltage(file, PART2_BATTERIES)?)?)
 }
 
 fn main() {
-    run_day(DAY, ex1, ex2);
+    match aoclib::cli_option("batteries") {
+        Some(batteries) => run_day(DAY, ex1, |file| total_joltage(file, batteries)),
+        None => run_day(DAY, ex1, ex2),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::DAY;
-    use std::fs;
 
-    use super::{ex1, ex2};
+    use num_bigint::BigUint;
+
+    use super::{ex1, ex2, total_joltage};
 
     #[test]
     fn test() {
@@ -71,10 +63,25 @@ mod tests {
         assert_eq!(expected_ex2, ex2(example).expect("ex2 failed"));
     }
 
+    #[test]
+    fn test_many_batteries() {
+        // 25 batteries out of 30 make a joltage far above i64 and u64
+        let bank = "123456789987654321123456789987";
+        let expected = "6789987654321123456789987".parse::<BigUint>().unwrap();
+        assert_eq!(
+            expected,
+            total_joltage(bank, 25).expect("25 batteries failed")
+        );
+        let twice = format!("{bank}\n{bank}\n");
+        assert_eq!(
+            expected * 2u8,
+            total_joltage(&twice, 25).expect("two banks failed")
+        );
+    }
+
     #[test]
     fn test_file() {
-        let file =
-            fs::read_to_string(format!("./inputs/{DAY}_1.txt")).expect("failed to read input file");
+        let file = aoc2025::read_input(DAY).expect("failed to read input file");
         let expected_ex1: i64 = 17405;
         let expected_ex2: i64 = 171990312704598;
         assert_eq!(expected_ex1, ex1(&file).expect("ex1 failed"));
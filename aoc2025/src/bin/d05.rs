@@ -1,19 +1,21 @@
 use aoc2025::run_day;
+use aoclib::interval::IntervalSet;
 
 use anyhow::{Context, Result};
 
 const DAY: &str = "d05";
 
-#[allow(clippy::type_complexity)]
-fn parse_input(file: &str, get_ing_list: bool) -> Result<(Vec<(i64, i64)>, Vec<i64>)> {
+fn parse_input(file: &str, get_ing_list: bool) -> Result<(IntervalSet<i64>, Vec<i64>)> {
     let mut parts = file.split("\n\n");
     let ranges = parts
         .next()
         .context("failed to find ranges section")?
         .lines()
         .filter_map(|l| l.split_once('-'))
-        .map(|(a, b)| Ok((a.parse::<i64>()?, b.parse::<i64>()?)))
-        .collect::<Result<Vec<(i64, i64)>>>()?;
+        .map(|(a, b)| Ok(a.parse::<i64>()?..=b.parse::<i64>()?))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .collect();
     if !get_ing_list {
         return Ok((ranges, Vec::new()));
     }
@@ -30,33 +32,15 @@ fn ex1(file: &str) -> Result<i64> {
     let (ranges, ingredients) = parse_input(file, true)?;
     let r = ingredients
         .iter()
-        .filter(|ing| ranges.iter().any(|(a, b)| (a..=b).contains(ing)))
+        .filter(|&&ing| ranges.contains(ing))
         .count();
 
     Ok(r as i64)
 }
 
 fn ex2(file: &str) -> Result<i64> {
-    let (mut ranges, _) = parse_input(file, false)?;
-    let mut count = 0;
-    ranges.sort_by(|a, b| a.0.cmp(&b.0));
-    let mut iter = ranges.iter().enumerate();
-    let mut prev = *iter.next().context("failed to extract first element")?.1;
-    for (i, (start, end)) in iter {
-        let mut merged = false;
-        if *start <= prev.1 + 1 {
-            merged = true;
-            if *end > prev.1 {
-                prev = (prev.0, *end);
-            }
-        }
-
-        if !merged || i == ranges.len() - 1 {
-            count += prev.1 - prev.0 + 1;
-            prev = (*start, *end);
-        }
-    }
-    Ok(count)
+    let (ranges, _) = parse_input(file, false)?;
+    Ok(ranges.len().try_into()?)
 }
 
 fn main() {
@@ -66,7 +50,6 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::DAY;
-    use std::fs;
 
     use super::{ex1, ex2};
 
@@ -108,8 +91,7 @@ mod tests {
 
     #[test]
     fn test_file() {
-        let file =
-            fs::read_to_string(format!("./inputs/{DAY}_1.txt")).expect("failed to read input file");
+        let file = aoc2025::read_input(DAY).expect("failed to read input file");
         let expected_ex1: i64 = 567;
         let expected_ex2: i64 = 354149806372909;
         assert_eq!(expected_ex1, ex1(&file).expect("ex1 failed"));
@@ -107,7 +107,6 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::DAY;
-    use std::fs;
 
     use super::{ex1, ex2};
 
@@ -126,8 +125,7 @@ mod tests {
 
     #[test]
     fn test_file() {
-        let file =
-            fs::read_to_string(format!("./inputs/{DAY}_1.txt")).expect("failed to read input file");
+        let file = aoc2025::read_input(DAY).expect("failed to read input file");
         let expected_ex1: i64 = 5552221122013;
         let expected_ex2: i64 = 11371597126232;
         assert_eq!(expected_ex1, ex1(&file).expect("ex1 failed"));
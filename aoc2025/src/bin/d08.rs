@@ -1,148 +1,49 @@
-use std::collections::{HashMap, HashSet};
-
 use aoc2025::run_day;
+use aoclib::disjoint_set::{DisjointSet, kruskal};
+use aoclib::geometry::Point3;
+use aoclib::kd_tree::KdTree;
 
 use anyhow::{Context, Result};
-use itertools::Itertools;
-use union_find::{QuickUnionUf, UnionByRank, UnionFind};
 
 const DAY: &str = "d08";
 
-#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
-struct JunctionBox {
-    x: i64,
-    y: i64,
-    z: i64,
-}
-
-impl JunctionBox {
-    fn dist(&self, other: &Self) -> f64 {
-        f64::sqrt(
-            (i64::pow(self.x - other.x, 2)
-                + i64::pow(self.y - other.y, 2)
-                + i64::pow(self.z - other.z, 2)) as f64,
-        )
-    }
-}
-
-impl From<(i64, i64, i64)> for JunctionBox {
-    fn from(value: (i64, i64, i64)) -> Self {
-        Self {
-            x: value.0,
-            y: value.1,
-            z: value.2,
-        }
-    }
-}
+type JunctionBox = Point3<i64>;
 
 fn parse_input(file: &str) -> Result<Vec<JunctionBox>> {
     file.lines()
-        .map(|l| -> Option<JunctionBox> {
-            l.split(',')
-                .filter_map(|e| e.parse::<i64>().ok())
-                .collect_tuple::<(i64, i64, i64)>()
-                .map(Into::into)
-        })
-        .collect::<Option<Vec<_>>>()
+        .map(str::parse)
+        .collect::<Result<Vec<_>>>()
         .context("failed to parse file")
 }
 
-fn compute_distances(boxes: &[JunctionBox]) -> Vec<((JunctionBox, JunctionBox), f64)> {
-    let distances_map: HashMap<(JunctionBox, JunctionBox), f64> = boxes
-        .iter()
-        .flat_map(|a| boxes.iter().map(|b| ((*a).clone(), (*b).clone())))
-        .filter(|(a, b)| !a.eq(b))
-        .map(move |(a, b)| {
-            let d = a.dist(&b);
-            if a < b { ((a, b), d) } else { ((b, a), d) }
-        })
-        .collect();
-
-    let mut distances: Vec<_> = distances_map.into_iter().collect();
-    distances.sort_by(|a, b| a.1.total_cmp(&b.1));
-    distances
-}
-
-fn compute_groups(distances: &[((JunctionBox, JunctionBox), f64)]) -> Vec<HashSet<JunctionBox>> {
-    let mut circuits: Vec<HashSet<JunctionBox>> = Vec::new();
-
-    for (pair, _) in distances.iter() {
-        let mut hs = HashSet::new();
-        hs.insert(pair.0.to_owned());
-        hs.insert(pair.1.to_owned());
-        circuits.push(hs);
-    }
-
-    let mut uf = QuickUnionUf::<UnionByRank>::new(circuits.len());
-    for (i, s1) in circuits.iter().enumerate() {
-        for (j, s2) in circuits.iter().enumerate() {
-            if !s1.is_disjoint(s2) {
-                uf.union(i, j);
-            }
-        }
-    }
-
-    let mut groups = vec![HashSet::new(); circuits.len()];
-    for (i, c) in circuits.iter().enumerate() {
-        let root = uf.find(i);
-        groups[root].extend(c.clone());
-    }
-
-    let mut groups: Vec<HashSet<_>> = groups.into_iter().filter(|s| !s.is_empty()).collect();
-    groups.sort_by_key(|a| std::cmp::Reverse(a.len()));
-    groups
-}
-
 fn ex1_implem(boxes: &[JunctionBox], num_circuits_to_count: usize) -> Result<i64> {
-    let distances = compute_distances(boxes);
-
-    let groups = compute_groups(&distances[..num_circuits_to_count]);
+    let tree = KdTree::new(boxes);
+    let mut circuits = DisjointSet::new(boxes.len());
+    for ((a, b), _) in tree.closest_pairs().take(num_circuits_to_count) {
+        circuits.union(a, b);
+    }
+    let mut sizes = circuits.components().map(|c| c.len()).collect::<Vec<_>>();
+    sizes.sort_by_key(|&len| std::cmp::Reverse(len));
 
-    Ok(groups.iter().take(3).map(|g| g.len()).product::<usize>() as i64)
+    Ok(sizes.iter().take(3).product::<usize>() as i64)
 }
 
 fn ex1(file: &str) -> Result<i64> {
     ex1_implem(parse_input(file)?.as_slice(), 1000)
 }
 
-fn divide_to_conquer(
-    distances: &[((JunctionBox, JunctionBox), f64)],
-    boxes_len: usize,
-    jump_size: usize,
-    start: usize,
-) -> (usize, i64) {
-    let mut i = start;
-    loop {
-        i += jump_size;
-        let groups = compute_groups(&distances[..i]);
-        if groups.len() == 1 && groups[0].len() == boxes_len {
-            println!("found in {} window", jump_size);
-            let last_conn = &distances[i - 1];
-            return (i, last_conn.0.0.x * last_conn.0.1.x);
-        }
-    }
-}
-
-fn ex2_implem(boxes: &[JunctionBox], start_num: usize) -> Result<i64> {
-    let distances = compute_distances(boxes);
-
-    let mut start = start_num;
-    let mut jump_size = start_num;
-
-    loop {
-        // Binary search would be better for worst-case scenario, but this is no such scenario :D
-        let (i, res) = divide_to_conquer(&distances, boxes.len(), jump_size, start);
-        if jump_size == 1 {
-            return Ok(res);
-        }
-        start = i - jump_size;
-        jump_size /= 10;
-    }
+/// Connects pairs from the closest one on, stopping at the one that joins every box into a
+/// single circuit.
+fn ex2_implem(boxes: &[JunctionBox]) -> Result<i64> {
+    let tree = KdTree::new(boxes);
+    let last = kruskal(boxes.len(), tree.closest_pairs().map(|(pair, _)| pair))
+        .find(|merge| merge.components == 1)
+        .context("boxes never form a single circuit")?;
+    Ok(boxes[last.a].x * boxes[last.b].x)
 }
 
 fn ex2(file: &str) -> Result<i64> {
-    let boxes = parse_input(file)?;
-    ex2_implem(boxes.as_slice(), 1000)
+    ex2_implem(parse_input(file)?.as_slice())
 }
 
 fn main() {
@@ -154,7 +55,6 @@ mod tests {
     use crate::{ex1_implem, ex2_implem, parse_input};
 
     use super::DAY;
-    use std::fs;
 
     use super::{ex1, ex2};
 
@@ -189,14 +89,13 @@ mod tests {
         );
         assert_eq!(
             expected_ex2,
-            ex2_implem(parse_input(example).unwrap().as_slice(), 10).expect("ex2 failed")
+            ex2_implem(parse_input(example).unwrap().as_slice()).expect("ex2 failed")
         );
     }
 
     #[test]
     fn test_file() {
-        let file =
-            fs::read_to_string(format!("./inputs/{DAY}_1.txt")).expect("failed to read input file");
+        let file = aoc2025::read_input(DAY).expect("failed to read input file");
         let expected_ex1: i64 = 68112;
         let expected_ex2: i64 = 44543856;
         assert_eq!(expected_ex1, ex1(&file).expect("ex1 failed"));
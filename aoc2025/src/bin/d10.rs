@@ -1,38 +1,65 @@
 use std::str::FromStr;
 
 use aoc2025::run_day;
+use aoclib::gf2::{BitMatrix, BitVec};
+#[cfg(feature = "ilp")]
+use aoclib::ilp::{IntegerProgram, Relation};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, ensure};
 
 const DAY: &str = "d10";
 
+#[cfg(not(any(feature = "ilp", feature = "z3")))]
+compile_error!("d10 needs the `ilp` or the `z3` feature");
+
 #[derive(Clone, Default)]
 struct Machine {
-    goal: u16,
-    buttons_masks: Vec<u16>,
+    goal: BitVec,
+    buttons: Vec<Vec<usize>>,
     joltage_req: Vec<i64>,
 }
 
 impl Machine {
-    fn find_goal_in_n_presses(&self, start: u16, presses: i64) -> bool {
-        if start == self.goal {
-            return true;
-        }
-        if presses == 0 {
-            return false;
-        }
-        for b in self.buttons_masks.iter() {
-            let modified = start ^ b;
-            if self.find_goal_in_n_presses(modified, presses - 1) {
-                return true;
-            }
+    /// Pressing a button twice cancels out, so the fewest presses is the lightest solution of
+    /// the system over GF(2) with one column per button.
+    fn min_presses_for_lights(&self) -> Option<usize> {
+        let toggles = BitMatrix::from_columns(self.goal.len(), &self.buttons);
+        Some(toggles.solve(&self.goal)?.min_weight().weight())
+    }
+
+    #[cfg(feature = "ilp")]
+    fn find_best_combinations_for_joltage(&self) -> Result<i64> {
+        self.find_best_combinations_for_joltage_ilp()
+    }
+
+    #[cfg(not(feature = "ilp"))]
+    fn find_best_combinations_for_joltage(&self) -> Result<i64> {
+        self.find_best_combinations_for_joltage_z3()
+            .context("no button presses reach the joltage requirements")
+    }
+
+    #[cfg(feature = "ilp")]
+    fn find_best_combinations_for_joltage_ilp(&self) -> Result<i64> {
+        let mut program = IntegerProgram::new(self.buttons.len());
+        for (index, joltage) in self.joltage_req.iter().enumerate() {
+            let coefficients = self
+                .buttons
+                .iter()
+                .map(|b| i64::from(b.contains(&index)))
+                .collect::<Vec<_>>();
+            program.constrain(&coefficients, Relation::Eq, *joltage)?;
         }
-        false
+        program.minimize(&vec![1; self.buttons.len()])?;
+        let solution = program
+            .solve()?
+            .context("no button presses reach the joltage requirements")?;
+        Ok(solution.objective)
     }
 
-    fn find_best_combinations_for_joltage(&self) -> Option<i64> {
+    #[cfg(feature = "z3")]
+    fn find_best_combinations_for_joltage_z3(&self) -> Option<i64> {
         let optimizer = z3::Optimize::new();
-        let z3_buttons = (0..self.buttons_masks.len())
+        let z3_buttons = (0..self.buttons.len())
             .map(|i| i as u32)
             .map(z3::ast::Int::new_const)
             .collect::<Vec<_>>();
@@ -41,11 +68,11 @@ impl Machine {
         });
         for (index, joltage) in self.joltage_req.iter().enumerate() {
             let affecting_buttons = self
-                .buttons_masks
+                .buttons
                 .iter()
                 .enumerate()
                 .filter_map(|(b_index, b)| {
-                    if b & (1 << index) != 0 {
+                    if b.contains(&index) {
                         Some(z3_buttons[b_index].clone())
                     } else {
                         None
@@ -53,8 +80,11 @@ impl Machine {
                 })
                 .collect::<Vec<_>>();
 
-            if affecting_buttons.is_empty() && self.goal != 0 {
-                return None;
+            if affecting_buttons.is_empty() {
+                if *joltage != 0 {
+                    return None;
+                }
+                continue;
             }
             let s = affecting_buttons.into_iter().reduce(|a, b| a + b)?;
             if s != 0 {
@@ -81,26 +111,27 @@ impl FromStr for Machine {
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         let closing_brack = s.find("]").context("failed to find closing bracket")?;
-        let mut goal = 0;
-        (s[1..closing_brack])
-            .chars()
-            .enumerate()
-            .for_each(|(i, c)| {
-                if c == '#' {
-                    goal |= 1 << i;
-                }
-            });
+        let lights = &s[1..closing_brack];
+        let goal = BitVec::from_indices(
+            lights.len(),
+            lights
+                .char_indices()
+                .filter(|&(_, c)| c == '#')
+                .map(|(i, _)| i),
+        );
         let opening_curly = s.find("{").context("failed to find opening curly")?;
-        let buttons_masks = (s[closing_brack + 3..opening_curly - 2])
+        let buttons = (s[closing_brack + 3..opening_curly - 2])
             .split(") (")
             .map(|b| {
-                let mut bitset = 0;
-                b.split(',').for_each(|c| {
-                    if let Ok(index) = c.parse::<usize>() {
-                        bitset |= 1 << index;
-                    }
-                });
-                Ok(bitset)
+                let indices = b
+                    .split(',')
+                    .filter_map(|c| c.parse::<usize>().ok())
+                    .collect::<Vec<_>>();
+                ensure!(
+                    indices.iter().all(|&i| i < goal.len()),
+                    "button {b} toggles a light out of range"
+                );
+                Ok(indices)
             })
             .collect::<Result<_>>()?;
         let joltage_req = (s[opening_curly + 1..s.len() - 1])
@@ -110,7 +141,7 @@ impl FromStr for Machine {
 
         Ok(Machine {
             goal,
-            buttons_masks,
+            buttons,
             joltage_req,
         })
     }
@@ -123,11 +154,10 @@ fn ex1(file: &str) -> Result<i64> {
         .map(Machine::from_str)
         .collect::<Result<Vec<_>>>()?;
     for m in machines.iter() {
-        let mut i = 1;
-        while !m.find_goal_in_n_presses(0, i) {
-            i += 1;
-        }
-        sum += i;
+        let presses = m
+            .min_presses_for_lights()
+            .context("no combination of buttons reaches the lights goal")?;
+        sum += presses as i64;
     }
     Ok(sum)
 }
@@ -137,12 +167,14 @@ fn ex2(file: &str) -> Result<i64> {
         .lines()
         .map(Machine::from_str)
         .collect::<Result<Vec<_>>>()?;
-    let sum = machines
+    machines
         .iter()
-        .filter_map(|m| m.find_best_combinations_for_joltage())
-        .sum::<i64>();
-
-    Ok(sum)
+        .enumerate()
+        .map(|(i, m)| {
+            m.find_best_combinations_for_joltage()
+                .with_context(|| format!("machine {}", i + 1))
+        })
+        .sum()
 }
 
 fn main() {
@@ -152,9 +184,8 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::DAY;
-    use std::fs;
 
-    use super::{ex1, ex2};
+    use super::{Machine, ex1, ex2};
 
     #[test]
     fn test() {
@@ -168,13 +199,45 @@ mod tests {
         assert_eq!(expected_ex2, ex2(example).expect("ex2 failed"));
     }
 
+    #[test]
+    fn test_wide_machine() {
+        let machine =
+            "[....................#] (0,20) (0) (1,2) {1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1,1}"
+                .parse::<Machine>()
+                .expect("failed to parse machine");
+        assert_eq!(Some(2), machine.min_presses_for_lights());
+        assert!("[..] (0,2) {1,1}".parse::<Machine>().is_err());
+    }
+
+    #[test]
+    fn test_unreachable_joltage() {
+        // no button increases the second counter
+        let err = ex2("[..] (0) {1,1}").expect_err("ex2 should fail");
+        assert_eq!("machine 1", err.to_string());
+    }
+
     #[test]
     fn test_file() {
-        let file =
-            fs::read_to_string(format!("./inputs/{DAY}_1.txt")).expect("failed to read input file");
+        let file = aoc2025::read_input(DAY).expect("failed to read input file");
         let expected_ex1: i64 = 422;
         let expected_ex2: i64 = 16361;
         assert_eq!(expected_ex1, ex1(&file).expect("ex1 failed"));
         assert_eq!(expected_ex2, ex2(&file).expect("ex2 failed"));
     }
+
+    #[cfg(all(feature = "ilp", feature = "z3"))]
+    #[test]
+    fn test_ilp_matches_z3() {
+        let file = aoc2025::read_input(DAY).expect("failed to read input file");
+        // counters no button increases, with and without joltage to reach
+        let untouched = "[..] (0) {1,0}\n[..] (0) {1,1}\n";
+        for l in file.lines().chain(untouched.lines()) {
+            let machine = l.parse::<Machine>().expect("failed to parse machine");
+            assert_eq!(
+                machine.find_best_combinations_for_joltage_z3(),
+                machine.find_best_combinations_for_joltage_ilp().ok(),
+                "{l}"
+            );
+        }
+    }
 }
@@ -1,87 +1,52 @@
-use std::{
-    collections::{HashMap, HashSet},
-    str::FromStr,
-};
-
 use aoc2025::run_day;
+use aoclib::graph::{Graph, PathCount};
 
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
+use num_bigint::BigUint;
 
 const DAY: &str = "d11";
 
-#[derive(Debug, Default, Clone)]
-struct Graph {
-    edges: HashMap<String, HashSet<String>>,
-}
-
-impl Graph {
-    fn count_paths_memoized(
-        &self,
-        start: &str,
-        out: &str,
-        memo: &mut HashMap<(String, String), i64>,
-    ) -> i64 {
-        let Some(nodes) = self.edges.get(start) else {
-            return 0;
+fn parse_graph(s: &str) -> Result<Graph> {
+    let mut g = Graph::new();
+    for l in s.lines() {
+        let Some((node, outputs)) = l.split_once(": ") else {
+            bail!("failed to parse device {l:?}");
         };
-        let path = (start.to_owned(), out.to_owned());
-        if let Some(&c) = memo.get(&path) {
-            return c;
-        }
-
-        let count = nodes
-            .iter()
-            .map(|n| {
-                if n == out {
-                    1
-                } else {
-                    self.count_paths_memoized(n, out, memo)
-                }
-            })
-            .sum();
-        memo.insert(path, count);
-        count
+        g.add_node(node);
+        outputs.split_whitespace().for_each(|o| g.add_edge(node, o));
     }
+    Ok(g)
 }
 
-impl FromStr for Graph {
-    type Err = anyhow::Error;
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let edges = s
-            .lines()
-            .filter_map(|l| {
-                let (node, remainder) = l.split_once(": ")?;
-                let outputs = remainder
-                    .split_whitespace()
-                    .map(ToOwned::to_owned)
-                    .collect::<HashSet<String>>();
-                Some((node.to_owned(), outputs))
-            })
-            .collect();
-        Ok(Self { edges })
+/// Number of paths from `start` to `out` visiting every device of `waypoints` in any order.
+fn count_paths(g: &Graph, start: &str, out: &str, waypoints: &[&str]) -> Result<BigUint> {
+    let id = |name: &str| g.id(name).with_context(|| format!("unknown device {name}"));
+    let waypoints = waypoints
+        .iter()
+        .map(|&w| id(w))
+        .collect::<Result<Vec<_>>>()?;
+    let (start, out) = (id(start)?, id(out)?);
+    match g.count_paths(start, out, &waypoints, &[])? {
+        PathCount::Finite(count) => Ok(count),
+        PathCount::Infinite => {
+            let cycle = g.cycle_between(start, out).unwrap_or_default();
+            let names = cycle.iter().map(|&id| g.name(id)).collect::<Vec<_>>();
+            bail!(
+                "infinitely many paths through the cycle of {}",
+                names.join(", ")
+            )
+        }
     }
 }
 
-fn ex1(file: &str) -> Result<i64> {
-    let g: Graph = file.parse()?;
-    Ok(g.count_paths_memoized("you", "out", &mut HashMap::new()))
+fn ex1(file: &str) -> Result<BigUint> {
+    let g = parse_graph(file)?;
+    count_paths(&g, "you", "out", &[])
 }
 
-fn ex2(file: &str) -> Result<i64> {
-    let g: Graph = file.parse()?;
-
-    let mut memo = HashMap::new();
-
-    let mut first_stop = "dac";
-    let mut second_stop = "fft";
-    let mut mid = g.count_paths_memoized(first_stop, second_stop, &mut memo);
-    if mid == 0 {
-        (first_stop, second_stop) = (second_stop, first_stop);
-        mid = g.count_paths_memoized(first_stop, second_stop, &mut memo);
-    }
-    let start = g.count_paths_memoized("svr", first_stop, &mut memo);
-    let end = g.count_paths_memoized(second_stop, "out", &mut memo);
-    Ok(start * mid * end)
+fn ex2(file: &str) -> Result<BigUint> {
+    let g = parse_graph(file)?;
+    count_paths(&g, "svr", "out", &["dac", "fft"])
 }
 
 fn main() {
@@ -91,7 +56,8 @@ fn main() {
 #[cfg(test)]
 mod tests {
     use super::DAY;
-    use std::fs;
+
+    use num_bigint::BigUint;
 
     use super::{ex1, ex2};
 
@@ -107,7 +73,7 @@ fff: out
 ggg: out
 hhh: ccc fff iii
 iii: out";
-        let expected_ex1: i64 = 5;
+        let expected_ex1 = BigUint::from(5u8);
         let example2 = "svr: aaa bbb
 aaa: fft
 fft: ccc
@@ -121,17 +87,33 @@ dac: fff
 fff: ggg hhh
 ggg: out
 hhh: out";
-        let expected_ex2: i64 = 2;
+        let expected_ex2 = BigUint::from(2u8);
         assert_eq!(expected_ex1, ex1(example).expect("ex1 failed"));
         assert_eq!(expected_ex2, ex2(example2).expect("ex2 failed"));
+        assert!(ex1(&format!("{example}\nggg: aaa")).is_err());
+    }
+
+    #[test]
+    fn test_many_paths() {
+        // 70 diamonds in a row make 2^70 paths, far above i64
+        let diamonds = (0..70)
+            .map(|i| {
+                format!(
+                    "n{i}: a{i} b{i}\na{i}: n{next}\nb{i}: n{next}",
+                    next = i + 1
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let graph = format!("you: n0\n{diamonds}\nn70: out");
+        assert_eq!(BigUint::from(1u8) << 70, ex1(&graph).expect("ex1 failed"));
     }
 
     #[test]
     fn test_file() {
-        let file =
-            fs::read_to_string(format!("./inputs/{DAY}_1.txt")).expect("failed to read input file");
-        let expected_ex1: i64 = 733;
-        let expected_ex2: i64 = 290219757077250;
+        let file = aoc2025::read_input(DAY).expect("failed to read input file");
+        let expected_ex1 = BigUint::from(733u16);
+        let expected_ex2 = BigUint::from(290219757077250u64);
         assert_eq!(expected_ex1, ex1(&file).expect("ex1 failed"));
         assert_eq!(expected_ex2, ex2(&file).expect("ex2 failed"));
     }
@@ -1,51 +1,38 @@
 use std::str::FromStr;
 
 use aoc2025::run_day;
+use aoclib::polyomino::{Layout, Polyomino, pack};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, ensure};
 
 const DAY: &str = "d12";
 
 #[derive(Debug, Clone)]
-struct Shape {
-    _width: u64,
-    _height: u64,
-    _shape: Vec<Vec<bool>>,
-    num_cells: u64,
+struct TreeFarm {
+    shapes: Vec<Polyomino>,
+    regions: Vec<((usize, usize), Vec<usize>)>,
 }
 
-impl FromStr for Shape {
-    type Err = anyhow::Error;
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let shape: Vec<Vec<bool>> = s
-            .lines()
-            .skip(1)
-            .map(|l| l.chars().map(|c| c == '#').collect())
-            .collect();
-        let num_cells = shape.iter().flatten().filter(|b| **b).count() as u64;
-        Ok(Self {
-            _width: shape[0].len() as u64,
-            _height: shape.len() as u64,
-            _shape: shape,
-            num_cells,
-        })
+impl TreeFarm {
+    /// Layout of the presents required under the tree of region `index`, `None` when they
+    /// don't fit.
+    fn arrange(&self, index: usize) -> Option<Layout> {
+        let ((width, height), presents_req) = &self.regions[index];
+        pack(*width, *height, &self.shapes, presents_req)
     }
 }
 
-#[derive(Debug, Clone)]
-struct TreeFarm {
-    shapes: Vec<Shape>,
-    regions: Vec<((u64, u64), Vec<u64>)>,
-}
-
 impl FromStr for TreeFarm {
     type Err = anyhow::Error;
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         let mut iter = s.split("\n\n");
-        let shapes = iter
+        let shapes: Vec<Polyomino> = iter
             .clone()
             .take(6)
-            .map(|s| s.parse::<Shape>())
+            .map(|s| {
+                let (_, shape) = s.split_once('\n').context("failed to parse shape")?;
+                shape.parse::<Polyomino>()
+            })
             .collect::<Result<_>>()?;
         let regions = iter
             .nth(6)
@@ -54,11 +41,15 @@ impl FromStr for TreeFarm {
             .map(|l| {
                 let (dims, num_per_shape) = l.split_once(": ").context("failed to parse region")?;
                 let (width_s, height_s) = dims.split_once("x").context("failed to parse region")?;
-                let (width, height) = (width_s.parse::<u64>()?, height_s.parse::<u64>()?);
+                let (width, height) = (width_s.parse::<usize>()?, height_s.parse::<usize>()?);
                 let nums_per_shape = num_per_shape
                     .split_whitespace()
-                    .map(|s| Ok(s.parse::<u64>()?))
+                    .map(|s| Ok(s.parse::<usize>()?))
                     .collect::<Result<Vec<_>>>()?;
+                ensure!(
+                    nums_per_shape.len() == shapes.len(),
+                    "region {l:?} doesn't have one count per shape"
+                );
                 Ok(((width, height), nums_per_shape))
             })
             .collect::<Result<_>>()?;
@@ -69,20 +60,10 @@ impl FromStr for TreeFarm {
 
 fn ex1(file: &str) -> Result<i64> {
     let tf: TreeFarm = file.parse()?;
-    let mut count = 0;
-    for ((r_width, r_height), presents_req) in tf.regions.iter() {
-        if (r_width * r_height)
-            >= presents_req
-                .iter()
-                .zip(tf.shapes.iter())
-                .map(|(pr, shape)| shape.num_cells * pr)
-                .sum()
-        {
-            // What a joke that this works
-            count += 1;
-        }
-    }
-    Ok(count)
+    let count = (0..tf.regions.len())
+        .filter(|&i| tf.arrange(i).is_some())
+        .count();
+    Ok(count as i64)
 }
 
 fn main() {
@@ -91,8 +72,7 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
-    use super::DAY;
-    use std::fs;
+    use super::{DAY, TreeFarm};
 
     use super::ex1;
 
@@ -133,15 +113,19 @@ mod tests {
 12x5: 1 0 1 0 3 2
 ";
         let expected_ex1: i64 = 2;
-        // the example input is more complex than the real-life input, and thus
-        // this simplistic approach does not work with it :D
-        assert_eq!(expected_ex1 + 1, ex1(example).expect("ex1 failed"));
+        assert_eq!(expected_ex1, ex1(example).expect("ex1 failed"));
+
+        let tf = example
+            .parse::<TreeFarm>()
+            .expect("failed to parse example");
+        let layout = tf.arrange(0).expect("presents should fit");
+        assert_eq!(2, layout.placements.len());
+        assert_eq!(16 - 2 * 7, layout.to_string().matches('.').count());
     }
 
     #[test]
     fn test_file() {
-        let file =
-            fs::read_to_string(format!("./inputs/{DAY}_1.txt")).expect("failed to read input file");
+        let file = aoc2025::read_input(DAY).expect("failed to read input file");
         let expected_ex1: i64 = 510;
         assert_eq!(expected_ex1, ex1(&file).expect("ex1 failed"));
     }
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+
+/// Expected answers of each input of a crate, as listed in the `answers.txt` file next to its
+/// `inputs/` directory: one `<input hash> <ex1> <ex2>` line per input, `-` for an unknown
+/// answer, optionally followed by a `# comment` naming the input. Keying the answers on the
+/// hash of the input (see [`crate::integrity::hash`]) means an input that was replaced or
+/// truncated is reported as unknown instead of wrong.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers {
+    by_hash: HashMap<String, [Option<String>; 2]>,
+}
+
+impl Answers {
+    /// Loads the answers file next to `inputs_dir`, empty when there is none.
+    pub fn load(inputs_dir: &Path) -> Result<Self> {
+        let path = inputs_dir
+            .parent()
+            .unwrap_or(Path::new("."))
+            .join("answers.txt");
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?
+            .parse()
+            .with_context(|| format!("invalid answers file {}", path.display()))
+    }
+
+    /// Expected answer of `part` (1 or 2) for the input with the given hash.
+    pub fn expected(&self, hash: &str, part: usize) -> Option<&str> {
+        self.by_hash
+            .get(hash)?
+            .get(part.checked_sub(1)?)?
+            .as_deref()
+    }
+}
+
+impl FromStr for Answers {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut by_hash = HashMap::new();
+        for (i, l) in s.lines().enumerate() {
+            let l = l.split_once('#').map_or(l, |(l, _comment)| l).trim();
+            if l.is_empty() {
+                continue;
+            }
+            let [hash, ex1, ex2] = l.split_whitespace().collect::<Vec<_>>()[..] else {
+                bail!("line {}: expected `<input hash> <ex1> <ex2>`", i + 1);
+            };
+            let answer = |a: &str| (a != "-").then(|| a.to_owned());
+            by_hash.insert(hash.to_owned(), [answer(ex1), answer(ex2)]);
+        }
+        Ok(Self { by_hash })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Answers;
+
+    #[test]
+    fn test_parse() {
+        let answers: Answers = "# comment
+0a1b 1152 6671 # d01_1
+
+2c3d 3 - # d01_example
+"
+        .parse()
+        .unwrap();
+        assert_eq!(Some("1152"), answers.expected("0a1b", 1));
+        assert_eq!(Some("6671"), answers.expected("0a1b", 2));
+        assert_eq!(Some("3"), answers.expected("2c3d", 1));
+        assert_eq!(None, answers.expected("2c3d", 2));
+        assert_eq!(None, answers.expected("4e5f", 1));
+        assert!("0a1b 1152".parse::<Answers>().is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use anyhow::{bail, Context, Result};
+
+use aoclib::crypt::{self, InputKey};
+use aoclib::input::read_file;
+use aoclib::integrity::{self, Manifest};
+
+const USAGE: &str = "usage: inputs <encrypt|decrypt|manifest> [--remove] <inputs dir>...
+
+encrypt  writes a `.txt.enc` file next to every `.txt` input of the directories
+decrypt  writes the `.txt` input of every `.txt.enc` file of the directories
+manifest records the hash of every input of the directories in the inputs.sha256
+         file next to them
+--remove deletes the source files once converted
+
+The key is read from AOC_INPUTS_KEY, from the file named by AOC_INPUTS_KEY_FILE,
+or from a .aoc-key file in the directory or one of its parents.";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Command {
+    Encrypt,
+    Decrypt,
+}
+
+/// Every file under `dir` whose name ends with `suffix`, sorted.
+fn files_with_suffix(dir: &Path, suffix: &str) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(files_with_suffix(&path, suffix)?);
+        } else if path.to_string_lossy().ends_with(suffix) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn write_manifest(dir: &Path) -> Result<()> {
+    let mut manifest = Manifest::default();
+    let mut files = files_with_suffix(dir, ".txt")?;
+    files.extend(files_with_suffix(
+        dir,
+        &format!(".txt.{}", crypt::EXTENSION),
+    )?);
+    for path in files {
+        let name = path.strip_prefix(dir)?.to_string_lossy();
+        let name = name
+            .strip_suffix(&format!(".{}", crypt::EXTENSION))
+            .unwrap_or(&name);
+        let file = read_file(&path)?;
+        for warning in integrity::corruption_warnings(&file) {
+            eprintln!("warning: {} {warning}", path.display());
+        }
+        manifest.insert(name, &file);
+    }
+    manifest.save(dir)?;
+    println!("{}", Manifest::path(dir).display());
+    Ok(())
+}
+
+fn convert_dir(command: Command, dir: &Path, remove: bool) -> Result<()> {
+    let key = InputKey::load(dir)?;
+    let suffix = match command {
+        Command::Encrypt => ".txt".to_owned(),
+        Command::Decrypt => format!(".txt.{}", crypt::EXTENSION),
+    };
+    for src in files_with_suffix(dir, &suffix)? {
+        let data = fs::read(&src).with_context(|| format!("failed to read {}", src.display()))?;
+        let (dst, converted) = match command {
+            Command::Encrypt => (crypt::encrypted_path(&src), key.encrypt(&data)),
+            Command::Decrypt => (
+                src.with_extension(""),
+                key.decrypt(&data)
+                    .with_context(|| format!("failed to decrypt {}", src.display()))?,
+            ),
+        };
+        fs::write(&dst, converted).with_context(|| format!("failed to write {}", dst.display()))?;
+        if remove {
+            fs::remove_file(&src).with_context(|| format!("failed to remove {}", src.display()))?;
+        }
+        println!("{} -> {}", src.display(), dst.display());
+    }
+    Ok(())
+}
+
+fn run(args: &[String]) -> Result<()> {
+    let Some((command, args)) = args.split_first() else {
+        bail!("missing command");
+    };
+    let command = match command.as_str() {
+        "encrypt" => Some(Command::Encrypt),
+        "decrypt" => Some(Command::Decrypt),
+        "manifest" => None,
+        other => bail!("unknown command {other}"),
+    };
+    let remove = args.iter().any(|a| a == "--remove");
+    let dirs = args.iter().filter(|a| *a != "--remove").collect::<Vec<_>>();
+    if dirs.is_empty() {
+        bail!("missing inputs directory");
+    }
+    for dir in dirs {
+        match command {
+            Some(command) => convert_dir(command, Path::new(dir), remove)?,
+            None => write_manifest(Path::new(dir))?,
+        }
+    }
+    Ok(())
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    if let Err(err) = run(&args) {
+        eprintln!("error: {err:#}\n\n{USAGE}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, ensure, Context, Result};
+
+/// How hands are ranked by their groups of equal cards, wildcards joining the largest group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Classification {
+    /// Group sizes in decreasing order, compared lexicographically: five of a kind, then four
+    /// of a kind, full house, three of a kind, two pairs, one pair and high card for hands of
+    /// five, as in poker and Camel Cards.
+    GroupSizes,
+    /// Group sizes in decreasing order of each kind of hand, from the weakest, other hands
+    /// being invalid.
+    Patterns(Vec<Vec<usize>>),
+}
+
+/// Rules for ranking hands of cards: by their kind, then card by card from the first one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandRules {
+    /// Strength of each card label, from 1, 0 being for wildcards.
+    values: HashMap<char, u8>,
+    hand_size: usize,
+    classification: Classification,
+}
+
+/// Sortable strength of a hand, weakest first.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandKey(Vec<u8>);
+
+impl HandRules {
+    /// Rules for hands of `hand_size` cards labelled with the characters of `order`, weakest
+    /// first, ranked by [`Classification::GroupSizes`] without wildcards.
+    pub fn new(order: &str, hand_size: usize) -> Result<Self> {
+        ensure!(order.chars().count() < 256, "more than 255 cards");
+        ensure!(hand_size < 256, "hands of more than 255 cards");
+        let mut values = HashMap::new();
+        for (label, value) in order.chars().zip(1..) {
+            ensure!(
+                values.insert(label, value).is_none(),
+                "duplicate card {label:?}"
+            );
+        }
+        Ok(Self {
+            values,
+            hand_size,
+            classification: Classification::GroupSizes,
+        })
+    }
+
+    /// Makes the `cards` wild: they join the largest group of other cards, which gives the
+    /// strongest hand when larger groups are stronger, and are weaker than every other card
+    /// when comparing hands of the same kind.
+    pub fn wildcards(&mut self, cards: &str) -> Result<&mut Self> {
+        for label in cards.chars() {
+            *self
+                .values
+                .get_mut(&label)
+                .with_context(|| format!("unknown wildcard {label:?}"))? = 0;
+        }
+        Ok(self)
+    }
+
+    /// Sets how the kind of a hand is found, at most 255 patterns.
+    pub fn classify(&mut self, classification: Classification) -> Result<&mut Self> {
+        if let Classification::Patterns(patterns) = &classification {
+            ensure!(patterns.len() < 256, "more than 255 kinds of hands");
+        }
+        self.classification = classification;
+        Ok(self)
+    }
+
+    /// Group sizes of `values` in decreasing order, wildcards included.
+    fn group_sizes(&self, values: &[u8]) -> Vec<usize> {
+        let mut counts = HashMap::new();
+        for &v in values.iter().filter(|&&v| v != 0) {
+            *counts.entry(v).or_insert(0) += 1;
+        }
+        let mut sizes = counts.into_values().collect::<Vec<usize>>();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        let wildcards = values.iter().filter(|&&v| v == 0).count();
+        match sizes.first_mut() {
+            Some(largest) => *largest += wildcards,
+            None => sizes.push(wildcards),
+        }
+        sizes
+    }
+
+    /// Key of the hand `cards`, failing when it has the wrong size, an unknown card, or matches
+    /// none of the [`Classification::Patterns`].
+    pub fn key(&self, cards: &str) -> Result<HandKey> {
+        let values = cards
+            .chars()
+            .map(|c| {
+                self.values
+                    .get(&c)
+                    .copied()
+                    .with_context(|| format!("invalid card {c:?} in {cards:?}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        ensure!(
+            values.len() == self.hand_size,
+            "hand {cards:?} doesn't have {} cards",
+            self.hand_size
+        );
+        let sizes = self.group_sizes(&values);
+        let mut key = match &self.classification {
+            Classification::GroupSizes => {
+                let mut key = sizes.iter().map(|&s| s as u8).collect::<Vec<_>>();
+                key.resize(self.hand_size, 0);
+                key
+            }
+            Classification::Patterns(patterns) => match patterns.iter().position(|p| *p == sizes) {
+                Some(kind) => vec![kind as u8],
+                None => bail!("hand {cards:?} matches no pattern"),
+            },
+        };
+        key.extend(values);
+        Ok(HandKey(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{Classification, HandRules};
+
+    const CAMEL: &str = "23456789TJQKA";
+
+    #[test]
+    fn test_camel_cards() {
+        let hands = ["32T3K", "T55J5", "KK677", "KTJJT", "QQQJA"];
+        let sorted = |rules: &HandRules| {
+            let mut sorted = hands.to_vec();
+            sorted.sort_by_cached_key(|h| rules.key(h).unwrap());
+            sorted
+        };
+        let mut rules = HandRules::new(CAMEL, 5).unwrap();
+        assert_eq!(
+            vec!["32T3K", "KTJJT", "KK677", "T55J5", "QQQJA"],
+            sorted(&rules)
+        );
+        rules.wildcards("J").unwrap();
+        assert_eq!(
+            vec!["32T3K", "KK677", "T55J5", "QQQJA", "KTJJT"],
+            sorted(&rules)
+        );
+        assert!(rules.key("JJJJJ").unwrap() < rules.key("22222").unwrap());
+        assert!(rules.key("JJJJJ").unwrap() > rules.key("AAAAK").unwrap());
+
+        assert!(rules.key("2345X").is_err());
+        assert!(rules.key("2345").is_err());
+        assert!(HandRules::new("AA", 5).is_err());
+        assert!(rules.wildcards("X").is_err());
+    }
+
+    #[test]
+    fn test_patterns() {
+        // three cards, where a run of three distinct cards beats a pair
+        let mut rules = HandRules::new("123", 3).unwrap();
+        let patterns = vec![vec![2, 1], vec![1, 1, 1], vec![3]];
+        rules.classify(Classification::Patterns(patterns)).unwrap();
+        assert!(rules.key("112").unwrap() < rules.key("123").unwrap());
+        assert!(rules.key("321").unwrap() < rules.key("111").unwrap());
+        assert!(rules.key("1234").is_err());
+        let mut rules = HandRules::new("123", 3).unwrap();
+        rules
+            .classify(Classification::Patterns(vec![vec![3]]))
+            .unwrap();
+        assert!(rules.key("112").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_wildcards_make_strongest_hand(hand in prop::collection::vec(0..4usize, 1..7)) {
+            let labels = ['a', 'b', 'c', 'w'];
+            let cards = hand.iter().map(|&c| labels[c]).collect::<String>();
+            let plain = HandRules::new("abcw", hand.len()).unwrap();
+            let mut wild = plain.clone();
+            wild.wildcards("w").unwrap();
+            // the kind of hand is the best one among every replacement of the wildcards
+            let best = (0..3usize.pow(hand.len() as u32))
+                .map(|mut replacements| {
+                    let replaced = cards
+                        .chars()
+                        .map(|c| match c {
+                            'w' => {
+                                let r = labels[replacements % 3];
+                                replacements /= 3;
+                                r
+                            }
+                            c => c,
+                        })
+                        .collect::<String>();
+                    plain.key(&replaced).unwrap().0[..hand.len()].to_vec()
+                })
+                .max();
+            prop_assert_eq!(best.unwrap(), wild.key(&cards).unwrap().0[..hand.len()].to_vec());
+        }
+    }
+}
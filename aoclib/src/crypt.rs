@@ -0,0 +1,139 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use sha2::{Digest, Sha256};
+
+/// Passphrase used to encrypt the inputs.
+pub const KEY_ENV: &str = "AOC_INPUTS_KEY";
+/// Path of a file holding the passphrase, when `AOC_INPUTS_KEY` isn't set.
+pub const KEY_FILE_ENV: &str = "AOC_INPUTS_KEY_FILE";
+/// Name of the key file looked up in the inputs directory and its parents.
+pub const KEY_FILE_NAME: &str = ".aoc-key";
+/// Extension appended to the name of an encrypted input.
+pub const EXTENSION: &str = "enc";
+
+const MAGIC: &[u8] = b"AOCENC1\n";
+const NONCE_LEN: usize = 12;
+
+/// Key encrypting the puzzle inputs, derived from a passphrase.
+#[derive(Clone)]
+pub struct InputKey([u8; 32]);
+
+impl InputKey {
+    pub fn from_passphrase(passphrase: &str) -> Self {
+        Self(Sha256::digest(passphrase.trim().as_bytes()).into())
+    }
+
+    /// Finds the key of the inputs in `inputs_dir`: `$AOC_INPUTS_KEY`, then the file named by
+    /// `$AOC_INPUTS_KEY_FILE`, then a `.aoc-key` file in `inputs_dir` or one of its parents.
+    pub fn load(inputs_dir: &Path) -> Result<Self> {
+        if let Ok(passphrase) = env::var(KEY_ENV) {
+            return Ok(Self::from_passphrase(&passphrase));
+        }
+        let key_file = match env::var_os(KEY_FILE_ENV) {
+            Some(path) => PathBuf::from(path),
+            None => inputs_dir
+                .canonicalize()
+                .unwrap_or_else(|_| inputs_dir.to_owned())
+                .ancestors()
+                .map(|dir| dir.join(KEY_FILE_NAME))
+                .find(|path| path.is_file())
+                .with_context(|| {
+                    format!(
+                        "no key to decrypt the inputs in {}: set {KEY_ENV}, or {KEY_FILE_ENV}, \
+                         or create a {KEY_FILE_NAME} file next to the inputs",
+                        inputs_dir.display()
+                    )
+                })?,
+        };
+        let passphrase = fs::read_to_string(&key_file)
+            .with_context(|| format!("failed to read key file {}", key_file.display()))?;
+        Ok(Self::from_passphrase(&passphrase))
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(&self.0.into())
+    }
+
+    /// Encrypts `plain`. The nonce is derived from the key and the content, so encrypting the
+    /// same input twice gives the same bytes and re-encrypting the tree doesn't touch the
+    /// files that didn't change.
+    pub fn encrypt(&self, plain: &[u8]) -> Vec<u8> {
+        let digest = Sha256::new()
+            .chain_update(self.0)
+            .chain_update(plain)
+            .finalize();
+        let nonce = Nonce::from_slice(&digest[..NONCE_LEN]);
+        let cipher_text = self
+            .cipher()
+            .encrypt(nonce, plain)
+            .expect("input too large to encrypt");
+        [MAGIC, nonce.as_slice(), &cipher_text].concat()
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let Some(data) = data.strip_prefix(MAGIC) else {
+            bail!("not an encrypted input");
+        };
+        if data.len() < NONCE_LEN {
+            bail!("truncated encrypted input");
+        }
+        let (nonce, cipher_text) = data.split_at(NONCE_LEN);
+        self.cipher()
+            .decrypt(Nonce::from_slice(nonce), cipher_text)
+            .ok()
+            .context("failed to decrypt input: wrong key or corrupted file")
+    }
+}
+
+/// Path of the encrypted version of `path`.
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(EXTENSION);
+    PathBuf::from(name)
+}
+
+/// Reads an encrypted input, looking for the key from its directory.
+pub fn read_encrypted(path: &Path) -> Result<String> {
+    let data = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let key = InputKey::load(path.parent().unwrap_or(Path::new(".")))?;
+    let plain = key
+        .decrypt(&data)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    String::from_utf8(plain).with_context(|| format!("{} is not valid UTF-8", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{encrypted_path, InputKey};
+
+    #[test]
+    fn test_round_trip() {
+        let key = InputKey::from_passphrase("correct horse battery staple\n");
+        let plain = b"seeds: 79 14 55 13\n";
+        let encrypted = key.encrypt(plain);
+        assert_ne!(&encrypted[..], &plain[..]);
+        assert_eq!(encrypted, key.encrypt(plain));
+        assert_eq!(plain.to_vec(), key.decrypt(&encrypted).unwrap());
+
+        let other = InputKey::from_passphrase("wrong");
+        assert!(other.decrypt(&encrypted).is_err());
+        assert!(key.decrypt(plain).is_err());
+        assert!(key.decrypt(&encrypted[..encrypted.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_encrypted_path() {
+        assert_eq!(
+            Path::new("inputs/d05_1.txt.enc"),
+            encrypted_path(Path::new("inputs/d05_1.txt"))
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use anyhow::{Context, Result};
+
+use crate::number_theory::crt;
+
+/// Times at which a deterministic walker is on a goal state, time `t` being the state after
+/// `t` steps. Every walk over finitely many states ends up looping: the states from
+/// `cycle_start` on repeat every `cycle_len` steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Walk {
+    /// Hits before the cycle, in increasing order.
+    pub tail_hits: Vec<u64>,
+    pub cycle_start: u64,
+    pub cycle_len: u64,
+    /// Hits during the first run of the cycle, in increasing order.
+    pub cycle_hits: Vec<u64>,
+}
+
+impl Walk {
+    /// Walks from `start` with `step` until a state repeats, recording the hits of `is_goal`.
+    /// Takes as many steps as there are distinct states on the walk.
+    pub fn detect<S, F, G>(start: S, mut step: F, is_goal: G) -> Self
+    where
+        S: Hash + Eq + Clone,
+        F: FnMut(&S) -> S,
+        G: Fn(&S) -> bool,
+    {
+        let mut seen = HashMap::new();
+        let mut hits = Vec::new();
+        let mut state = start;
+        let mut time = 0;
+        let cycle_start = loop {
+            if let Some(&first) = seen.get(&state) {
+                break first;
+            }
+            if is_goal(&state) {
+                hits.push(time);
+            }
+            let next = step(&state);
+            seen.insert(state, time);
+            state = next;
+            time += 1;
+        };
+        let split = hits.partition_point(|&t| t < cycle_start);
+        Self {
+            cycle_hits: hits.split_off(split),
+            tail_hits: hits,
+            cycle_start,
+            cycle_len: time - cycle_start,
+        }
+    }
+
+    /// Whether the walker is on a goal at time `t`.
+    pub fn hits_at(&self, t: u64) -> bool {
+        if t < self.cycle_start {
+            return self.tail_hits.binary_search(&t).is_ok();
+        }
+        let in_cycle = self.cycle_start + (t - self.cycle_start) % self.cycle_len;
+        self.cycle_hits.binary_search(&in_cycle).is_ok()
+    }
+}
+
+/// First time at which every walker is on a goal, `None` when it never happens. Fails when
+/// the lcm of the cycle lengths or the time overflows a `u64`.
+///
+/// Times before every walker is in its cycle are checked one by one. After that, each walker
+/// hits at a set of residues modulo its cycle length, and the sets are merged pairwise with
+/// the generalised Chinese remainder theorem: the number of residues can grow as the product
+/// of the numbers of hits per cycle.
+pub fn first_common_hit(walks: &[Walk]) -> Result<Option<u64>> {
+    let Some((first, others)) = walks.split_first() else {
+        return Ok(None);
+    };
+    let settled = walks
+        .iter()
+        .map(|w| w.cycle_start)
+        .max()
+        .expect("there are walks");
+    let early = first
+        .tail_hits
+        .iter()
+        .copied()
+        .chain(
+            (first.cycle_start..settled)
+                .step_by(first.cycle_len as usize)
+                .flat_map(|base| {
+                    first
+                        .cycle_hits
+                        .iter()
+                        .map(move |&h| base - first.cycle_start + h)
+                }),
+        )
+        .filter(|&t| t < settled)
+        .filter(|&t| others.iter().all(|w| w.hits_at(t)))
+        .min();
+    if early.is_some() {
+        return Ok(early);
+    }
+
+    let mut residues = vec![(0, 1)];
+    for walk in walks {
+        let mut merged = Vec::new();
+        for &a in &residues {
+            for &h in &walk.cycle_hits {
+                merged.extend(crt(&[a, (h % walk.cycle_len, walk.cycle_len)])?);
+            }
+        }
+        merged.sort_unstable();
+        merged.dedup();
+        residues = merged;
+    }
+    let times = residues
+        .into_iter()
+        .map(|(r, m)| match r < settled {
+            true => (settled - r)
+                .div_ceil(m)
+                .checked_mul(m)
+                .and_then(|t| t.checked_add(r))
+                .with_context(|| format!("hit at {r} modulo {m} after {settled} overflows u64")),
+            false => Ok(r),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(times.into_iter().min())
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{first_common_hit, Walk};
+
+    #[test]
+    fn test_detect() {
+        // 0 -> 1 -> 2 -> 3 -> 4 -> 2
+        let walk = Walk::detect(0, |&s| if s == 4 { 2 } else { s + 1 }, |&s| s % 2 == 1);
+        assert_eq!(vec![1], walk.tail_hits);
+        assert_eq!((2, 3), (walk.cycle_start, walk.cycle_len));
+        assert_eq!(vec![3], walk.cycle_hits);
+        assert!(walk.hits_at(6) && !walk.hits_at(7) && walk.hits_at(9));
+    }
+
+    #[test]
+    fn test_first_common_hit() {
+        // hits at 2 mod 3 and at 1 mod 2, the lcm of the first hits being 2
+        let a = Walk::detect(0, |&s| if s == 3 { 1 } else { s + 1 }, |&s| s == 2);
+        let b = Walk::detect(0, |&s| if s == 2 { 1 } else { s + 1 }, |&s| s == 1);
+        assert_eq!(Some(5), first_common_hit(&[a.clone(), b]).unwrap());
+        let never = Walk::detect(0, |&s| (s + 1) % 3, |&s| s == 0);
+        assert_eq!(None, first_common_hit(&[a.clone(), never]).unwrap());
+        assert_eq!(Some(2), first_common_hit(&[a]).unwrap());
+        assert_eq!(None, first_common_hit(&[]).unwrap());
+
+        // cycles of 2^63 and 3 steps only meet after more than u64::MAX steps
+        let huge = |cycle_len, hit| Walk {
+            tail_hits: Vec::new(),
+            cycle_start: 0,
+            cycle_len,
+            cycle_hits: vec![hit],
+        };
+        assert!(first_common_hit(&[huge(1 << 63, 1), huge(3, 2)]).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_first_common_hit(
+            walkers in prop::collection::vec(
+                (prop::collection::vec(0..6usize, 6), prop::collection::vec(any::<bool>(), 6)),
+                1..4,
+            )
+        ) {
+            let walks = walkers
+                .iter()
+                .map(|(next, goal)| Walk::detect(0, |&s| next[s], |&s| goal[s]))
+                .collect::<Vec<_>>();
+            // every walker is in its cycle after 6 steps, and the cycle lengths divide 60
+            let brute = (0..66).find(|&t| {
+                walkers.iter().all(|(next, goal)| {
+                    let state = (0..t).fold(0, |s, _| next[s]);
+                    goal[state]
+                })
+            });
+            prop_assert_eq!(brute, first_common_hit(&walks).unwrap());
+        }
+    }
+}
@@ -0,0 +1,193 @@
+/// Disjoint-set forest over the elements `0..len`, with path compression and union by size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    /// Size of the component of each root, meaningless for other elements.
+    size: Vec<usize>,
+    components: usize,
+}
+
+impl DisjointSet {
+    /// `len` singletons.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+            components: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Representative of the component of `x`.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut x = x;
+        while self.parent[x] != root {
+            x = std::mem::replace(&mut self.parent[x], root);
+        }
+        root
+    }
+
+    /// Merges the components of `a` and `b`, returning the root of the merged component, or
+    /// `None` when they already were the same.
+    pub fn union(&mut self, a: usize, b: usize) -> Option<usize> {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return None;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.components -= 1;
+        Some(a)
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Number of elements in the component of `x`.
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// The elements of each component, in increasing order, components ordered by their
+    /// smallest element.
+    pub fn components(&mut self) -> impl Iterator<Item = Vec<usize>> {
+        let mut by_root: Vec<Option<usize>> = vec![None; self.len()];
+        let mut components: Vec<Vec<usize>> = Vec::with_capacity(self.components);
+        for x in 0..self.len() {
+            let root = self.find(x);
+            let i = *by_root[root].get_or_insert_with(|| {
+                components.push(Vec::with_capacity(self.size[root]));
+                components.len() - 1
+            });
+            components[i].push(x);
+        }
+        components.into_iter()
+    }
+}
+
+/// Edge of a [`kruskal`] run that merged two components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Merge {
+    /// Index of the edge in the edge list.
+    pub edge: usize,
+    pub a: usize,
+    pub b: usize,
+    /// Size of the merged component.
+    pub size: usize,
+    /// Number of components left after the merge.
+    pub components: usize,
+}
+
+/// Kruskal's algorithm over `len` elements, returned by [`kruskal`]: yields a [`Merge`] for each
+/// edge joining two components, skipping the others.
+#[derive(Debug, Clone)]
+pub struct Kruskal<I> {
+    edges: I,
+    set: DisjointSet,
+    next: usize,
+}
+
+/// Processes `edges`, already sorted by weight, in order. The merges form a minimum spanning
+/// forest; the run can be stopped at any point, e.g. when a single component is left.
+pub fn kruskal<I>(len: usize, edges: I) -> Kruskal<I::IntoIter>
+where
+    I: IntoIterator<Item = (usize, usize)>,
+{
+    Kruskal {
+        edges: edges.into_iter(),
+        set: DisjointSet::new(len),
+        next: 0,
+    }
+}
+
+impl<I> Kruskal<I> {
+    /// Components formed by the edges processed so far.
+    pub fn set(&mut self) -> &mut DisjointSet {
+        &mut self.set
+    }
+}
+
+impl<I: Iterator<Item = (usize, usize)>> Iterator for Kruskal<I> {
+    type Item = Merge;
+
+    fn next(&mut self) -> Option<Merge> {
+        for (a, b) in self.edges.by_ref() {
+            let edge = self.next;
+            self.next += 1;
+            if let Some(root) = self.set.union(a, b) {
+                return Some(Merge {
+                    edge,
+                    a,
+                    b,
+                    size: self.set.size[root],
+                    components: self.set.components,
+                });
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{kruskal, DisjointSet, Merge};
+
+    #[test]
+    fn test_disjoint_set() {
+        let mut set = DisjointSet::new(6);
+        assert_eq!(6, set.component_count());
+        assert!(set.union(0, 1).is_some());
+        assert!(set.union(2, 1).is_some());
+        assert!(set.union(0, 2).is_none());
+        assert!(set.union(4, 5).is_some());
+        assert_eq!(3, set.component_count());
+        assert_eq!(3, set.size(1));
+        assert_eq!(1, set.size(3));
+        assert!(set.same(0, 2) && !set.same(0, 4));
+        assert_eq!(
+            vec![vec![0, 1, 2], vec![3], vec![4, 5]],
+            set.components().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_kruskal() {
+        let edges = [(0, 1), (1, 2), (0, 2), (3, 4), (2, 3)];
+        let merges = kruskal(5, edges).collect::<Vec<_>>();
+        assert_eq!(4, merges.len());
+        assert_eq!(
+            Merge {
+                edge: 4,
+                a: 2,
+                b: 3,
+                size: 5,
+                components: 1
+            },
+            merges[3]
+        );
+
+        let mut run = kruskal(5, edges);
+        run.by_ref().take(2).for_each(drop);
+        assert_eq!(3, run.set().component_count());
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+use std::ops::Range;
+
+/// Last `k` bytes of a stream, with the count of each byte value so that whether they are all
+/// distinct is known in O(1) after each byte.
+#[derive(Debug, Clone)]
+pub struct DistinctWindow {
+    k: usize,
+    bytes: VecDeque<u8>,
+    counts: [usize; 256],
+    /// Byte values seen more than once in the window.
+    repeated: usize,
+}
+
+impl DistinctWindow {
+    /// # Panics
+    ///
+    /// Panics when `k` is zero.
+    pub fn new(k: usize) -> Self {
+        assert!(k > 0, "empty window");
+        Self {
+            k,
+            bytes: VecDeque::with_capacity(k),
+            counts: [0; 256],
+            repeated: 0,
+        }
+    }
+
+    /// Adds `byte`, dropping the oldest one when the window is full. Returns whether the
+    /// window is then full of distinct bytes.
+    pub fn push(&mut self, byte: u8) -> bool {
+        if self.bytes.len() == self.k {
+            let old = self.bytes.pop_front().expect("the window is full");
+            self.counts[old as usize] -= 1;
+            if self.counts[old as usize] == 1 {
+                self.repeated -= 1;
+            }
+        }
+        self.bytes.push_back(byte);
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 2 {
+            self.repeated += 1;
+        }
+        self.bytes.len() == self.k && self.repeated == 0
+    }
+}
+
+/// Start of each window of `k` distinct bytes of `bytes`, in increasing order.
+///
+/// # Panics
+///
+/// Panics when `k` is zero.
+pub fn distinct_windows(bytes: &[u8], k: usize) -> impl Iterator<Item = usize> + '_ {
+    let mut window = DistinctWindow::new(k);
+    bytes
+        .iter()
+        .enumerate()
+        .filter(move |&(_, &b)| window.push(b))
+        .map(move |(i, _)| i + 1 - k)
+}
+
+/// Start of the first window of `k` distinct bytes of `bytes`, e.g. the start-of-packet
+/// marker of 2022 d6, `None` when there is none.
+///
+/// # Panics
+///
+/// Panics when `k` is zero.
+pub fn first_distinct_window(bytes: &[u8], k: usize) -> Option<usize> {
+    distinct_windows(bytes, k).next()
+}
+
+/// Longest run of distinct bytes of `bytes`, the first one among the longest.
+///
+/// Grows the run by one byte at a time, shrinking it from the start until the new byte isn't
+/// repeated anymore: every byte enters and leaves it once.
+pub fn longest_distinct_run(bytes: &[u8]) -> Range<usize> {
+    let mut counts = [0usize; 256];
+    let mut longest = 0..0;
+    let mut start = 0;
+    for (end, &b) in bytes.iter().enumerate() {
+        counts[b as usize] += 1;
+        while counts[b as usize] > 1 {
+            counts[bytes[start] as usize] -= 1;
+            start += 1;
+        }
+        if end + 1 - start > longest.len() {
+            longest = start..end + 1;
+        }
+    }
+    longest
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use proptest::prelude::*;
+
+    use super::{distinct_windows, first_distinct_window, longest_distinct_run};
+
+    fn is_distinct(bytes: &[u8]) -> bool {
+        bytes.iter().collect::<HashSet<_>>().len() == bytes.len()
+    }
+
+    #[test]
+    fn test_distinct_windows() {
+        let stream = b"mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        assert_eq!(Some(3), first_distinct_window(stream, 4));
+        assert_eq!(
+            Some(1),
+            first_distinct_window(b"bvwbjplbgvbhsrlpgdmjqwftvncz", 4)
+        );
+        assert_eq!(Some(5), first_distinct_window(stream, 14));
+        assert_eq!(None, first_distinct_window(b"aabbaabb", 3));
+        assert_eq!(vec![0, 1], distinct_windows(b"abca", 3).collect::<Vec<_>>());
+        assert_eq!(7..16, longest_distinct_run(b"abcabcbbgjlsphdzb"));
+        assert_eq!(0..0, longest_distinct_run(b""));
+        assert_eq!(0..1, longest_distinct_run(b"aaaa"));
+        let all = (0..=255).chain(0..=255).collect::<Vec<u8>>();
+        assert_eq!(0..256, longest_distinct_run(&all));
+        assert_eq!(257, distinct_windows(&all, 256).count());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_distinct_windows(bytes in prop::collection::vec(0..6u8, 0..30), k in 1..8usize) {
+            let brute = bytes
+                .windows(k)
+                .enumerate()
+                .filter(|(_, w)| is_distinct(w))
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>();
+            prop_assert_eq!(brute.first().copied(), first_distinct_window(&bytes, k));
+            prop_assert_eq!(brute, distinct_windows(&bytes, k).collect::<Vec<_>>());
+
+            let longest = longest_distinct_run(&bytes);
+            prop_assert!(is_distinct(&bytes[longest.clone()]));
+            let brute_len = (0..=bytes.len())
+                .flat_map(|i| (i..=bytes.len()).map(move |j| i..j))
+                .filter(|r| is_distinct(&bytes[r.clone()]))
+                .map(|r| r.len())
+                .max();
+            prop_assert_eq!(brute_len, Some(longest.len()));
+        }
+    }
+}
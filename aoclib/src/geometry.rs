@@ -0,0 +1,130 @@
+use std::ops::{Add, Sub};
+use std::str::FromStr;
+
+use anyhow::{Context, Error, Result};
+
+/// Point or vector in 3D space.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: Copy> Point3<T> {
+    /// Coordinate along `axis`: 0 for x, 1 for y, 2 for z.
+    pub fn get(&self, axis: usize) -> T {
+        match axis {
+            0 => self.x,
+            1 => self.y,
+            2 => self.z,
+            _ => panic!("no axis {axis} in 3D"),
+        }
+    }
+}
+
+impl Point3<i64> {
+    /// Exact square of the euclidean distance, which orders points like the distance itself.
+    /// Doesn't overflow for coordinates within ±2^62.
+    pub fn dist2(&self, other: &Self) -> u128 {
+        [
+            self.x.abs_diff(other.x),
+            self.y.abs_diff(other.y),
+            self.z.abs_diff(other.z),
+        ]
+        .into_iter()
+        .map(|d| u128::from(d) * u128::from(d))
+        .sum()
+    }
+
+    pub fn manhattan(&self, other: &Self) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    pub fn chebyshev(&self, other: &Self) -> u64 {
+        self.x
+            .abs_diff(other.x)
+            .max(self.y.abs_diff(other.y))
+            .max(self.z.abs_diff(other.z))
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self { x, y, z }
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+/// Parses `x,y,z`.
+impl<T: FromStr> FromStr for Point3<T>
+where
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut coords = s.split(',').map(|c| c.trim().parse::<T>());
+        let mut next = || -> Result<T> {
+            coords
+                .next()
+                .with_context(|| format!("missing coordinate in {s:?}"))?
+                .with_context(|| format!("invalid coordinate in {s:?}"))
+        };
+        let point = Self::new(next()?, next()?, next()?);
+        if coords.next().is_some() {
+            anyhow::bail!("too many coordinates in {s:?}");
+        }
+        Ok(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Point3;
+
+    #[test]
+    fn test_metrics() {
+        let a = Point3::new(162, 817, 812);
+        let b = Point3::new(425, 690, 689);
+        assert_eq!(263 * 263 + 127 * 127 + 123 * 123, a.dist2(&b));
+        assert_eq!(263 + 127 + 123, a.manhattan(&b));
+        assert_eq!(263, a.chebyshev(&b));
+
+        let far = Point3::new(-(1 << 62), -(1 << 62), -(1 << 62));
+        let near = Point3::new(1 << 62, 1 << 62, 1 << 62);
+        assert_eq!(3 << 126, far.dist2(&near));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Point3::new(1, -2, 3),
+            "1,-2,3".parse::<Point3<i64>>().unwrap()
+        );
+        assert!("1,2".parse::<Point3<i64>>().is_err());
+        assert!("1,2,3,4".parse::<Point3<i64>>().is_err());
+        assert!("1,a,3".parse::<Point3<i64>>().is_err());
+    }
+}
@@ -0,0 +1,344 @@
+use std::fmt;
+use std::iter;
+
+/// Fixed-length vector over GF(2), packed in 64-bit words.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitVec {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitVec {
+    /// `len` zeros.
+    pub fn zeros(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    /// Vector of length `len` with ones at `indices`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when an index is out of range.
+    pub fn from_indices(len: usize, indices: impl IntoIterator<Item = usize>) -> Self {
+        let mut v = Self::zeros(len);
+        indices.into_iter().for_each(|i| v.set(i, true));
+        v
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.len, "bit {i} out of range for length {}", self.len);
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    pub fn set(&mut self, i: usize, value: bool) {
+        assert!(i < self.len, "bit {i} out of range for length {}", self.len);
+        let mask = 1 << (i % 64);
+        if value {
+            self.words[i / 64] |= mask;
+        } else {
+            self.words[i / 64] &= !mask;
+        }
+    }
+
+    /// Adds `other`, which must have the same length.
+    pub fn xor_assign(&mut self, other: &Self) {
+        assert_eq!(self.len, other.len, "xor of vectors of different lengths");
+        self.words
+            .iter_mut()
+            .zip(&other.words)
+            .for_each(|(a, b)| *a ^= b);
+    }
+
+    /// Number of ones.
+    pub fn weight(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// Indices of the ones, in increasing order.
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(i, &w)| {
+            let mut w = w;
+            std::iter::from_fn(move || {
+                (w != 0).then(|| {
+                    let bit = w.trailing_zeros() as usize;
+                    w &= w - 1;
+                    i * 64 + bit
+                })
+            })
+        })
+    }
+}
+
+impl fmt::Debug for BitVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (0..self.len).try_for_each(|i| write!(f, "{}", u8::from(self.get(i))))
+    }
+}
+
+/// Solutions of a linear system over GF(2): `particular` plus any sum of vectors of
+/// `null_space`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolutionSpace {
+    pub particular: BitVec,
+    pub null_space: Vec<BitVec>,
+}
+
+impl SolutionSpace {
+    /// Every solution, `2^null_space.len()` of them, each differing from the previous one by
+    /// a single null-space vector. Lazy, with a counter of one bit per null-space vector, so
+    /// there is no limit on the dimension, only on how many solutions can be looked at.
+    pub fn solutions(&self) -> impl Iterator<Item = BitVec> + '_ {
+        let mut counter = vec![false; self.null_space.len()];
+        let mut next = Some(self.particular.clone());
+        iter::from_fn(move || {
+            let solution = next.take()?;
+            // gray code: flip the basis vector of the bit set by incrementing the counter, the
+            // lowest clear one, the counter having wrapped around when there is none
+            if let Some(bit) = counter.iter().position(|&b| !b) {
+                counter[..bit].fill(false);
+                counter[bit] = true;
+                let mut following = solution.clone();
+                following.xor_assign(&self.null_space[bit]);
+                next = Some(following);
+            }
+            Some(solution)
+        })
+    }
+
+    /// Solution with the fewest ones, found by enumerating the whole space: exponential in the
+    /// dimension of the null space.
+    pub fn min_weight(&self) -> BitVec {
+        self.solutions()
+            .min_by_key(BitVec::weight)
+            .expect("a solution space is never empty")
+    }
+}
+
+/// Matrix over GF(2), one [`BitVec`] per row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitMatrix {
+    rows: Vec<BitVec>,
+    cols: usize,
+}
+
+impl BitMatrix {
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self {
+            rows: vec![BitVec::zeros(cols); rows],
+            cols,
+        }
+    }
+
+    /// Matrix of `rows` rows whose column `j` has ones at the indices of `columns[j]`.
+    pub fn from_columns(rows: usize, columns: &[impl AsRef<[usize]>]) -> Self {
+        let mut m = Self::zeros(rows, columns.len());
+        for (j, column) in columns.iter().enumerate() {
+            column.as_ref().iter().for_each(|&i| m.set(i, j, true));
+        }
+        m
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        self.rows[row].get(col)
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: bool) {
+        self.rows[row].set(col, value)
+    }
+
+    /// Solves `self · x = rhs` by Gaussian elimination, `None` when there is no solution.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `rhs` doesn't have one entry per row.
+    pub fn solve(&self, rhs: &BitVec) -> Option<SolutionSpace> {
+        assert_eq!(
+            self.rows(),
+            rhs.len(),
+            "right hand side of the wrong length"
+        );
+        let mut rows = self.rows.clone();
+        let mut rhs = rhs.clone();
+        // reduced row echelon form, pivots[r] being the pivot column of row r
+        let mut pivots = Vec::new();
+        for col in 0..self.cols {
+            let r = pivots.len();
+            let Some(found) = (r..rows.len()).find(|&i| rows[i].get(col)) else {
+                continue;
+            };
+            rows.swap(r, found);
+            let (b, value) = (rhs.get(r), rhs.get(found));
+            rhs.set(r, value);
+            rhs.set(found, b);
+            for i in 0..rows.len() {
+                if i != r && rows[i].get(col) {
+                    let pivot_row = rows[r].clone();
+                    rows[i].xor_assign(&pivot_row);
+                    let value = rhs.get(i) ^ rhs.get(r);
+                    rhs.set(i, value);
+                }
+            }
+            pivots.push(col);
+        }
+        if (pivots.len()..rows.len()).any(|i| rhs.get(i)) {
+            return None;
+        }
+
+        let mut particular = BitVec::zeros(self.cols);
+        for (r, &col) in pivots.iter().enumerate() {
+            particular.set(col, rhs.get(r));
+        }
+        let null_space = (0..self.cols)
+            .filter(|col| !pivots.contains(col))
+            .map(|free| {
+                let mut v = BitVec::zeros(self.cols);
+                v.set(free, true);
+                for (r, &col) in pivots.iter().enumerate() {
+                    v.set(col, rows[r].get(free));
+                }
+                v
+            })
+            .collect();
+        Some(SolutionSpace {
+            particular,
+            null_space,
+        })
+    }
+
+    /// `self · x`.
+    pub fn mul_vec(&self, x: &BitVec) -> BitVec {
+        assert_eq!(self.cols, x.len(), "vector of the wrong length");
+        let mut product = BitVec::zeros(self.rows());
+        for (i, row) in self.rows.iter().enumerate() {
+            let parity = row
+                .words
+                .iter()
+                .zip(&x.words)
+                .map(|(a, b)| (a & b).count_ones())
+                .sum::<u32>();
+            product.set(i, parity % 2 == 1);
+        }
+        product
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{BitMatrix, BitVec, SolutionSpace};
+
+    #[test]
+    fn test_large_null_space() {
+        // 2^130 solutions, more than a u128 can count
+        let space = SolutionSpace {
+            particular: BitVec::zeros(130),
+            null_space: (0..130).map(|i| BitVec::from_indices(130, [i])).collect(),
+        };
+        let first = space
+            .solutions()
+            .take(4)
+            .map(|s| s.ones().collect::<Vec<_>>());
+        assert_eq!(
+            vec![vec![], vec![0], vec![0, 1], vec![1]],
+            first.collect::<Vec<_>>()
+        );
+        let small = SolutionSpace {
+            particular: BitVec::zeros(2),
+            null_space: vec![BitVec::from_indices(2, [0]), BitVec::from_indices(2, [1])],
+        };
+        assert_eq!(4, small.solutions().count());
+    }
+
+    #[test]
+    fn test_bitvec() {
+        let mut v = BitVec::from_indices(130, [0, 64, 129]);
+        assert_eq!(3, v.weight());
+        assert_eq!(vec![0, 64, 129], v.ones().collect::<Vec<_>>());
+        v.xor_assign(&BitVec::from_indices(130, [64, 65]));
+        assert_eq!(vec![0, 65, 129], v.ones().collect::<Vec<_>>());
+        v.set(0, false);
+        assert!(!v.get(0) && v.get(65));
+        assert_eq!("0101", format!("{:?}", BitVec::from_indices(4, [1, 3])));
+    }
+
+    #[test]
+    fn test_min_weight() {
+        // lights [.##.], buttons (3) (1,3) (2) (2,3) (0,2) (0,1)
+        let buttons: [&[usize]; 6] = [&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]];
+        let m = BitMatrix::from_columns(4, &buttons);
+        let goal = BitVec::from_indices(4, [1, 2]);
+        let space = m.solve(&goal).unwrap();
+        assert_eq!(2, space.null_space.len());
+        assert_eq!(4, space.solutions().count());
+        assert!(space.solutions().all(|x| m.mul_vec(&x) == goal));
+        assert_eq!(2, space.min_weight().weight());
+
+        let m = BitMatrix::from_columns(2, &[[0], [0]]);
+        assert_eq!(None, m.solve(&BitVec::from_indices(2, [1])));
+    }
+
+    fn system() -> impl Strategy<Value = (BitMatrix, BitVec)> {
+        (1..6usize, 1..8usize).prop_flat_map(|(rows, cols)| {
+            (
+                prop::collection::vec(prop::collection::vec(any::<bool>(), cols), rows),
+                prop::collection::vec(any::<bool>(), rows),
+            )
+                .prop_map(move |(entries, rhs)| {
+                    let mut m = BitMatrix::zeros(rows, cols);
+                    for (i, row) in entries.iter().enumerate() {
+                        for (j, &b) in row.iter().enumerate() {
+                            m.set(i, j, b);
+                        }
+                    }
+                    let rhs = BitVec::from_indices(rows, (0..rows).filter(|&i| rhs[i]));
+                    (m, rhs)
+                })
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_solve((m, rhs) in system()) {
+            let all = (0..1usize << m.cols())
+                .map(|bits| BitVec::from_indices(m.cols(), (0..m.cols()).filter(|j| bits >> j & 1 == 1)))
+                .filter(|x| m.mul_vec(x) == rhs)
+                .collect::<Vec<_>>();
+            match m.solve(&rhs) {
+                None => prop_assert!(all.is_empty()),
+                Some(space) => {
+                    let mut solutions = space.solutions().collect::<Vec<_>>();
+                    solutions.sort_by_key(|x| format!("{x:?}"));
+                    solutions.dedup();
+                    prop_assert_eq!(all.len(), solutions.len());
+                    prop_assert!(solutions.iter().all(|x| all.contains(x)));
+                    let min = all.iter().map(BitVec::weight).min().unwrap();
+                    prop_assert_eq!(min, space.min_weight().weight());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,538 @@
+use std::collections::HashMap;
+use std::ops::AddAssign;
+
+use anyhow::{ensure, Result};
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+/// Directed graph whose nodes are named by strings, interned as the ids `0..len`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Graph {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    successors: Vec<Vec<usize>>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Id of `name`, interning it when it is new.
+    pub fn add_node(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_owned());
+        self.ids.insert(name.to_owned(), id);
+        self.successors.push(Vec::new());
+        id
+    }
+
+    /// Adds an edge from `from` to `to`, interning both, unless it already exists.
+    pub fn add_edge(&mut self, from: &str, to: &str) {
+        let (from, to) = (self.add_node(from), self.add_node(to));
+        if !self.successors[from].contains(&to) {
+            self.successors[from].push(to);
+        }
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: usize) -> &str {
+        &self.names[id]
+    }
+
+    pub fn successors(&self, id: usize) -> &[usize] {
+        &self.successors[id]
+    }
+
+    /// Every node before its successors, `None` when the graph has a cycle.
+    pub fn topological_order(&self) -> Option<Vec<usize>> {
+        let mut in_degree = vec![0; self.len()];
+        self.successors
+            .iter()
+            .flatten()
+            .for_each(|&to| in_degree[to] += 1);
+        let mut order = (0..self.len())
+            .filter(|&id| in_degree[id] == 0)
+            .collect::<Vec<_>>();
+        let mut next = 0;
+        while let Some(&id) = order.get(next) {
+            next += 1;
+            for &to in &self.successors[id] {
+                in_degree[to] -= 1;
+                if in_degree[to] == 0 {
+                    order.push(to);
+                }
+            }
+        }
+        (order.len() == self.len()).then_some(order)
+    }
+
+    /// Strongly connected components, each before the components it has edges to.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        let mut components = tarjan(&self.successors);
+        components.reverse();
+        components
+    }
+
+    /// Nodes of a cycle lying on a walk from `source` to `sink` that doesn't go on after
+    /// reaching `sink`, `None` when every such walk is a simple path.
+    pub fn cycle_between(&self, source: usize, sink: usize) -> Option<Vec<usize>> {
+        let successors = self.restricted(sink, &vec![false; self.len()]);
+        let reached = reachable(&successors, source);
+        let mut predecessors = vec![Vec::new(); self.len()];
+        for (from, tos) in successors.iter().enumerate() {
+            tos.iter().for_each(|&to| predecessors[to].push(from));
+        }
+        let reaching = reachable(&predecessors, sink);
+        tarjan(&successors)
+            .into_iter()
+            .filter(|c| is_cyclic(&successors, c))
+            .find(|c| c.iter().any(|&id| reached[id] && reaching[id]))
+    }
+
+    /// Number of walks from `source` to `sink` going through every node of `waypoints`, in any
+    /// order, and through none of `forbidden`, a walk ending as soon as it reaches `sink`.
+    ///
+    /// Dynamic programming over the strongly connected components in topological order,
+    /// keeping one count per subset of the waypoints visited so far: exponential in the number
+    /// of waypoints only. A walk going through a cycle can loop through it any number of times,
+    /// so the count is [`PathCount::Infinite`] as soon as one does.
+    pub fn count_paths(
+        &self,
+        source: usize,
+        sink: usize,
+        waypoints: &[usize],
+        forbidden: &[usize],
+    ) -> Result<PathCount> {
+        ensure!(waypoints.len() < 32, "too many waypoints");
+        let mut bit = vec![0; self.len()];
+        waypoints
+            .iter()
+            .enumerate()
+            .for_each(|(i, &id)| bit[id] |= 1 << i);
+        let mut banned = vec![false; self.len()];
+        forbidden.iter().for_each(|&id| banned[id] = true);
+        let successors = self.restricted(sink, &banned);
+        let mut components = tarjan(&successors);
+        components.reverse();
+        let mut component_of = vec![0; self.len()];
+        for (c, component) in components.iter().enumerate() {
+            component.iter().for_each(|&id| component_of[id] = c);
+        }
+
+        let subsets = 1 << waypoints.len();
+        // paths[id][visited]: walks from source to id through exactly the visited waypoints
+        let mut paths = vec![Vec::new(); self.len()];
+        if !banned[source] {
+            paths[source] = vec![PathCount::zero(); subsets];
+            paths[source][bit[source]] = PathCount::Finite(BigUint::from(1u8));
+        }
+        for (c, component) in components.iter().enumerate() {
+            if is_cyclic(&successors, component) {
+                // a walk entering the component can visit all of its nodes, in infinitely many
+                // ways
+                let all = component.iter().fold(0, |acc, &id| acc | bit[id]);
+                let mut inside = vec![PathCount::zero(); subsets];
+                for &id in component {
+                    for (visited, count) in paths[id].iter().enumerate() {
+                        if !count.is_zero() {
+                            inside[visited | all] = PathCount::Infinite;
+                        }
+                    }
+                }
+                if inside.iter().all(PathCount::is_zero) {
+                    continue;
+                }
+                component.iter().for_each(|&id| paths[id] = inside.clone());
+            }
+            for &id in component {
+                let from = std::mem::take(&mut paths[id]);
+                if from.is_empty() {
+                    continue;
+                }
+                for &to in &successors[id] {
+                    if component_of[to] == c {
+                        continue;
+                    }
+                    let into = &mut paths[to];
+                    if into.is_empty() {
+                        *into = vec![PathCount::zero(); subsets];
+                    }
+                    for (visited, count) in from.iter().enumerate() {
+                        into[visited | bit[to]] += count;
+                    }
+                }
+                paths[id] = from;
+            }
+        }
+        Ok(paths[sink]
+            .get(subsets - 1)
+            .cloned()
+            .unwrap_or_else(PathCount::zero))
+    }
+
+    /// Number of paths from `source` to `sink` never visiting a node twice, going through every
+    /// node of `waypoints` and through none of `forbidden`.
+    ///
+    /// Enumerates the paths one by one by an iterative depth-first search: exponential, but
+    /// finite even when the graph has cycles.
+    pub fn count_simple_paths(
+        &self,
+        source: usize,
+        sink: usize,
+        waypoints: &[usize],
+        forbidden: &[usize],
+    ) -> BigUint {
+        let mut wanted = vec![false; self.len()];
+        waypoints.iter().for_each(|&id| wanted[id] = true);
+        let wanted_count = wanted.iter().filter(|&&w| w).count();
+        let mut on_path = vec![false; self.len()];
+        forbidden.iter().for_each(|&id| on_path[id] = true);
+        if on_path[source] {
+            return BigUint::zero();
+        }
+
+        let mut count = BigUint::zero();
+        // current path, with the index of the next successor to try from each node
+        let mut path = vec![(source, 0)];
+        on_path[source] = true;
+        let mut visited_waypoints = usize::from(wanted[source]);
+        while let Some((id, next)) = path.last_mut() {
+            let id = *id;
+            if id == sink {
+                if visited_waypoints == wanted_count {
+                    count += 1u8;
+                }
+                *next = self.successors[id].len();
+            }
+            match self.successors[id].get(*next) {
+                Some(&to) => {
+                    *next += 1;
+                    if !on_path[to] {
+                        on_path[to] = true;
+                        visited_waypoints += usize::from(wanted[to]);
+                        path.push((to, 0));
+                    }
+                }
+                None => {
+                    path.pop();
+                    visited_waypoints -= usize::from(wanted[id]);
+                    on_path[id] = false;
+                }
+            }
+        }
+        count
+    }
+
+    /// Edges of the graph without the ones out of `sink` and the ones from or to `banned`
+    /// nodes.
+    fn restricted(&self, sink: usize, banned: &[bool]) -> Vec<Vec<usize>> {
+        self.successors
+            .iter()
+            .enumerate()
+            .map(|(id, tos)| match id == sink || banned[id] {
+                true => Vec::new(),
+                false => tos.iter().copied().filter(|&to| !banned[to]).collect(),
+            })
+            .collect()
+    }
+}
+
+/// Number of walks found by [`Graph::count_paths`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathCount {
+    Finite(BigUint),
+    Infinite,
+}
+
+impl PathCount {
+    fn zero() -> Self {
+        Self::Finite(BigUint::zero())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        matches!(self, Self::Finite(n) if n.is_zero())
+    }
+}
+
+impl AddAssign<&PathCount> for PathCount {
+    fn add_assign(&mut self, other: &PathCount) {
+        match (&mut *self, other) {
+            (Self::Finite(a), Self::Finite(b)) => *a += b,
+            (_, Self::Infinite) => *self = Self::Infinite,
+            (Self::Infinite, _) => {}
+        }
+    }
+}
+
+/// Whether a component has a cycle: it has several nodes or a node with an edge to itself.
+fn is_cyclic(successors: &[Vec<usize>], component: &[usize]) -> bool {
+    component.len() > 1 || successors[component[0]].contains(&component[0])
+}
+
+/// Nodes reachable from `start` following `successors`.
+fn reachable(successors: &[Vec<usize>], start: usize) -> Vec<bool> {
+    let mut reached = vec![false; successors.len()];
+    reached[start] = true;
+    let mut todo = vec![start];
+    while let Some(id) = todo.pop() {
+        for &to in &successors[id] {
+            if !std::mem::replace(&mut reached[to], true) {
+                todo.push(to);
+            }
+        }
+    }
+    reached
+}
+
+/// Strongly connected components by Tarjan's algorithm, each after the components it has
+/// edges to. Iterative, with an explicit stack of the nodes being explored.
+fn tarjan(successors: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    const UNVISITED: usize = usize::MAX;
+    let len = successors.len();
+    let (mut index, mut low) = (vec![UNVISITED; len], vec![0; len]);
+    let mut on_stack = vec![false; len];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut next_index = 0;
+    // nodes being explored, with the index of their next successor to look at
+    let mut exploring = Vec::new();
+    for root in 0..len {
+        if index[root] != UNVISITED {
+            continue;
+        }
+        exploring.push((root, 0));
+        while let Some(&(id, next)) = exploring.last() {
+            if index[id] == UNVISITED {
+                (index[id], low[id]) = (next_index, next_index);
+                next_index += 1;
+                stack.push(id);
+                on_stack[id] = true;
+            }
+            if let Some(&to) = successors[id].get(next) {
+                exploring.last_mut().expect("not empty").1 += 1;
+                if index[to] == UNVISITED {
+                    exploring.push((to, 0));
+                } else if on_stack[to] {
+                    low[id] = low[id].min(index[to]);
+                }
+                continue;
+            }
+            exploring.pop();
+            if let Some(&(parent, _)) = exploring.last() {
+                low[parent] = low[parent].min(low[id]);
+            }
+            if low[id] == index[id] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == id {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use proptest::prelude::*;
+
+    use super::{Graph, PathCount};
+
+    fn finite(n: u8) -> PathCount {
+        PathCount::Finite(BigUint::from(n))
+    }
+
+    fn graph(edges: &str) -> Graph {
+        let mut g = Graph::new();
+        for line in edges.lines() {
+            let (from, tos) = line.split_once(": ").unwrap();
+            tos.split_whitespace().for_each(|to| g.add_edge(from, to));
+        }
+        g
+    }
+
+    #[test]
+    fn test_count_paths() {
+        let g = graph(
+            "svr: aaa bbb
+aaa: fft
+fft: ccc
+bbb: tty
+tty: ccc
+ccc: ddd eee
+ddd: hub
+hub: fff
+eee: dac
+dac: fff
+fff: ggg hhh
+ggg: out
+hhh: out",
+        );
+        let id = |name: &str| g.id(name).unwrap();
+        let count = |waypoints: &[&str], forbidden: &[&str]| {
+            let waypoints = waypoints.iter().map(|&w| id(w)).collect::<Vec<_>>();
+            let forbidden = forbidden.iter().map(|&f| id(f)).collect::<Vec<_>>();
+            g.count_paths(id("svr"), id("out"), &waypoints, &forbidden)
+                .unwrap()
+        };
+        assert_eq!(finite(8), count(&[], &[]));
+        assert_eq!(finite(2), count(&["dac", "fft"], &[]));
+        assert_eq!(finite(2), count(&["fft", "dac"], &[]));
+        assert_eq!(finite(4), count(&[], &["fft"]));
+        assert_eq!(finite(0), count(&["fft"], &["dac", "hub"]));
+        assert_eq!(finite(1), count(&["dac", "fft", "ggg"], &[]));
+        let (svr, out) = (id("svr"), id("out"));
+        assert_eq!(BigUint::from(8u8), g.count_simple_paths(svr, out, &[], &[]));
+        assert_eq!(None, g.cycle_between(svr, out));
+    }
+
+    #[test]
+    fn test_cycles() {
+        let mut g = graph(
+            "a: b
+b: c
+c: b d
+d: e
+e: f
+f: e",
+        );
+        let id = |name: &str| g.id(name).unwrap();
+        let (a, b, c, d, e) = (id("a"), id("b"), id("c"), id("d"), id("e"));
+        let components = g.strongly_connected_components();
+        assert_eq!(4, components.len());
+        assert_eq!(vec![a], components[0]);
+        assert_eq!(vec![d], components[2]);
+        assert_eq!(None, g.topological_order());
+
+        let mut cycle = g.cycle_between(a, d).unwrap();
+        cycle.sort_unstable();
+        assert_eq!(vec![b, c], cycle);
+        assert_eq!(PathCount::Infinite, g.count_paths(a, d, &[], &[]).unwrap());
+        assert_eq!(BigUint::from(1u8), g.count_simple_paths(a, d, &[], &[]));
+        assert_eq!(finite(0), g.count_paths(a, d, &[], &[c]).unwrap());
+        // walks end on the sink, the cycle through it doesn't count
+        assert_eq!(None, g.cycle_between(d, e));
+        assert_eq!(finite(1), g.count_paths(d, e, &[], &[]).unwrap());
+        assert_eq!(PathCount::Infinite, g.count_paths(b, e, &[c], &[]).unwrap());
+
+        g.add_edge("d", "d");
+        assert_eq!(vec![d], g.cycle_between(c, e).unwrap());
+    }
+
+    #[test]
+    fn test_deep_graph() {
+        // a long chain ending in a long cycle with an exit, too deep for a recursive search
+        let mut g = Graph::new();
+        let len = 200_000;
+        for i in 0..len {
+            g.add_edge(&i.to_string(), &(i + 1).to_string());
+        }
+        g.add_edge(&len.to_string(), &(len / 2).to_string());
+        g.add_edge(&(len / 2).to_string(), "exit");
+        assert_eq!(len / 2 + 2, g.strongly_connected_components().len());
+        let (source, sink) = (g.id("0").unwrap(), g.id("exit").unwrap());
+        let count = g.count_paths(source, sink, &[], &[]).unwrap();
+        assert_eq!(PathCount::Infinite, count);
+        assert_eq!(
+            BigUint::from(1u8),
+            g.count_simple_paths(source, sink, &[], &[])
+        );
+    }
+
+    #[test]
+    fn test_big_counts() {
+        // 100 diamonds in a row: 2^100 paths
+        let mut g = Graph::new();
+        for i in 0..100 {
+            let (from, to) = (i.to_string(), (i + 1).to_string());
+            g.add_edge(&from, &format!("{i}a"));
+            g.add_edge(&from, &format!("{i}b"));
+            g.add_edge(&format!("{i}a"), &to);
+            g.add_edge(&format!("{i}b"), &to);
+        }
+        let (source, sink) = (g.id("0").unwrap(), g.id("100").unwrap());
+        let count = g.count_paths(source, sink, &[], &[]).unwrap();
+        assert_eq!(PathCount::Finite(BigUint::from(1u8) << 100), count);
+    }
+
+    /// Paths from `id` to `sink` as lists of nodes, by depth-first search.
+    fn all_paths(g: &Graph, id: usize, sink: usize) -> Vec<Vec<usize>> {
+        if id == sink {
+            return vec![vec![sink]];
+        }
+        g.successors(id)
+            .iter()
+            .flat_map(|&to| all_paths(g, to, sink))
+            .map(|mut path| {
+                path.insert(0, id);
+                path
+            })
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn prop_count_paths(
+            edges in prop::collection::vec((0..8usize, 0..8usize), 0..20),
+            waypoints in prop::collection::vec(0..8usize, 0..3),
+            forbidden in prop::collection::vec(0..8usize, 0..2),
+        ) {
+            // edges from lower to higher nodes only, to stay acyclic
+            let mut g = Graph::new();
+            (0..8).for_each(|i| { g.add_node(&i.to_string()); });
+            for (a, b) in edges.into_iter().filter(|(a, b)| a != b) {
+                g.add_edge(&a.min(b).to_string(), &a.max(b).to_string());
+            }
+            let expected = all_paths(&g, 0, 7)
+                .iter()
+                .filter(|path| waypoints.iter().all(|w| path.contains(w)))
+                .filter(|path| !forbidden.iter().any(|f| path.contains(f)))
+                .count();
+            let count = g.count_paths(0, 7, &waypoints, &forbidden).unwrap();
+            prop_assert_eq!(PathCount::Finite(BigUint::from(expected)), count);
+            prop_assert_eq!(
+                BigUint::from(expected),
+                g.count_simple_paths(0, 7, &waypoints, &forbidden)
+            );
+        }
+
+        #[test]
+        fn prop_cycles(edges in prop::collection::vec((0..8usize, 0..8usize), 0..20)) {
+            let mut g = Graph::new();
+            (0..8).for_each(|i| { g.add_node(&i.to_string()); });
+            edges.iter().for_each(|(a, b)| g.add_edge(&a.to_string(), &b.to_string()));
+            let mut nodes = g.strongly_connected_components().concat();
+            nodes.sort_unstable();
+            prop_assert_eq!((0..8).collect::<Vec<_>>(), nodes);
+            let count = g.count_paths(0, 7, &[], &[]).unwrap();
+            match g.cycle_between(0, 7) {
+                Some(_) => prop_assert_eq!(PathCount::Infinite, count),
+                None => prop_assert_eq!(
+                    PathCount::Finite(g.count_simple_paths(0, 7, &[], &[])),
+                    count
+                ),
+            }
+        }
+    }
+}
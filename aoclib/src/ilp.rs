@@ -0,0 +1,428 @@
+use anyhow::{bail, ensure, Result};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Signed, Zero};
+
+/// Relation between the two sides of a constraint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Le,
+    Eq,
+    Ge,
+}
+
+/// `coefficients · x (relation) rhs`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Constraint {
+    coefficients: Vec<BigRational>,
+    relation: Relation,
+    rhs: BigRational,
+}
+
+/// Outcome of solving the linear relaxation of a program.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LpOutcome {
+    Optimal {
+        values: Vec<BigRational>,
+        objective: BigRational,
+    },
+    Infeasible,
+    Unbounded,
+}
+
+/// Optimal solution of an [`IntegerProgram`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntegerSolution {
+    pub values: Vec<i64>,
+    pub objective: i64,
+}
+
+/// Program minimising a linear objective over non-negative integer variables subject to linear
+/// constraints, solved exactly with a rational simplex and branch-and-bound. Meant for small
+/// programs: every node of the search solves its relaxation from scratch.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntegerProgram {
+    vars: usize,
+    constraints: Vec<Constraint>,
+    objective: Vec<BigRational>,
+}
+
+fn ratio(v: i64) -> BigRational {
+    BigRational::from_integer(BigInt::from(v))
+}
+
+impl IntegerProgram {
+    /// Program over `vars` variables, minimising 0 until [`IntegerProgram::minimize`] is called.
+    pub fn new(vars: usize) -> Self {
+        Self {
+            vars,
+            constraints: Vec::new(),
+            objective: vec![BigRational::zero(); vars],
+        }
+    }
+
+    /// Adds `coefficients · x (relation) rhs`, `coefficients` having one entry per variable.
+    pub fn constrain(
+        &mut self,
+        coefficients: &[i64],
+        relation: Relation,
+        rhs: i64,
+    ) -> Result<&mut Self> {
+        ensure!(
+            coefficients.len() == self.vars,
+            "constraint has {} coefficients for {} variables",
+            coefficients.len(),
+            self.vars
+        );
+        self.constraints.push(Constraint {
+            coefficients: coefficients.iter().map(|&c| ratio(c)).collect(),
+            relation,
+            rhs: ratio(rhs),
+        });
+        Ok(self)
+    }
+
+    /// Sets the objective to minimise, one coefficient per variable.
+    pub fn minimize(&mut self, objective: &[i64]) -> Result<&mut Self> {
+        ensure!(
+            objective.len() == self.vars,
+            "objective has {} coefficients for {} variables",
+            objective.len(),
+            self.vars
+        );
+        self.objective = objective.iter().map(|&c| ratio(c)).collect();
+        Ok(self)
+    }
+
+    /// Solves the program without the integrality constraints.
+    pub fn solve_relaxation(&self) -> LpOutcome {
+        Tableau::solve(self.vars, &self.constraints, &self.objective)
+    }
+
+    /// Optimal integer solution, `None` when there is none. Fails when a variable is unbounded,
+    /// even if the objective isn't: the search only ends because every variable is.
+    pub fn solve(&self) -> Result<Option<IntegerSolution>> {
+        if self.solve_relaxation() == LpOutcome::Infeasible {
+            return Ok(None);
+        }
+        for var in 0..self.vars {
+            let mut objective = vec![BigRational::zero(); self.vars];
+            objective[var] = -BigRational::one();
+            if Tableau::solve(self.vars, &self.constraints, &objective) == LpOutcome::Unbounded {
+                bail!("unbounded variable {var}");
+            }
+        }
+        let mut best: Option<(BigRational, Vec<BigRational>)> = None;
+        let mut nodes = vec![Vec::new()];
+        while let Some(bounds) = nodes.pop() {
+            let constraints = [&self.constraints[..], &bounds[..]].concat();
+            let (values, objective) = match Tableau::solve(self.vars, &constraints, &self.objective)
+            {
+                LpOutcome::Optimal { values, objective } => (values, objective),
+                LpOutcome::Infeasible => continue,
+                LpOutcome::Unbounded => unreachable!("every variable is bounded"),
+            };
+            if best.as_ref().is_some_and(|(b, _)| objective >= *b) {
+                continue;
+            }
+            let Some((var, value)) = values.iter().enumerate().find(|(_, v)| !v.is_integer())
+            else {
+                best = Some((objective, values));
+                continue;
+            };
+            let mut unit = vec![BigRational::zero(); self.vars];
+            unit[var] = BigRational::one();
+            let mut branch = |relation, rhs| {
+                let mut bounds = bounds.clone();
+                bounds.push(Constraint {
+                    coefficients: unit.clone(),
+                    relation,
+                    rhs,
+                });
+                nodes.push(bounds);
+            };
+            branch(Relation::Ge, value.ceil());
+            branch(Relation::Le, value.floor());
+        }
+        best.map(|(objective, values)| {
+            let int = |v: &BigRational| -> Result<i64> {
+                v.to_integer()
+                    .try_into()
+                    .map_err(|_| anyhow::anyhow!("solution value {v} doesn't fit in i64"))
+            };
+            Ok(IntegerSolution {
+                values: values.iter().map(int).collect::<Result<_>>()?,
+                objective: int(&objective)?,
+            })
+        })
+        .transpose()
+    }
+}
+
+/// Dense simplex tableau in canonical form: each row has a basic variable with a unit column.
+struct Tableau {
+    rows: Vec<Vec<BigRational>>,
+    /// Reduced costs, the last entry being minus the objective value.
+    costs: Vec<BigRational>,
+    basis: Vec<usize>,
+    /// Number of columns, the right hand side excluded.
+    cols: usize,
+}
+
+impl Tableau {
+    fn pivot(&mut self, row: usize, col: usize) {
+        let pivot = self.rows[row][col].clone();
+        self.rows[row].iter_mut().for_each(|v| *v /= &pivot);
+        let pivot_row = self.rows[row].clone();
+        let eliminate = |target: &mut Vec<BigRational>| {
+            let factor = target[col].clone();
+            if !factor.is_zero() {
+                for (v, p) in target.iter_mut().zip(&pivot_row) {
+                    *v -= &factor * p;
+                }
+            }
+        };
+        for (i, other) in self.rows.iter_mut().enumerate() {
+            if i != row {
+                eliminate(other);
+            }
+        }
+        eliminate(&mut self.costs);
+        self.basis[row] = col;
+    }
+
+    /// Runs the simplex with Bland's rule, only letting the first `enterable` columns enter
+    /// the basis. Returns `false` when the objective is unbounded.
+    fn optimize(&mut self, enterable: usize) -> bool {
+        loop {
+            let Some(col) = (0..enterable).find(|&j| self.costs[j].is_negative()) else {
+                return true;
+            };
+            let leaving = (0..self.rows.len())
+                .filter(|&i| self.rows[i][col].is_positive())
+                .min_by(|&a, &b| {
+                    let ratio_a = &self.rows[a][self.cols] / &self.rows[a][col];
+                    let ratio_b = &self.rows[b][self.cols] / &self.rows[b][col];
+                    ratio_a
+                        .cmp(&ratio_b)
+                        .then(self.basis[a].cmp(&self.basis[b]))
+                });
+            let Some(row) = leaving else {
+                return false;
+            };
+            self.pivot(row, col);
+        }
+    }
+
+    /// Two-phase simplex minimising `objective · x` subject to `constraints` and `x >= 0`.
+    fn solve(vars: usize, constraints: &[Constraint], objective: &[BigRational]) -> LpOutcome {
+        // columns: variables, then one slack per inequality, then one artificial per row
+        let slacks = constraints
+            .iter()
+            .filter(|c| c.relation != Relation::Eq)
+            .count();
+        let artificial = vars + slacks;
+        let cols = artificial + constraints.len();
+        let mut rows = Vec::with_capacity(constraints.len());
+        let mut slack = vars;
+        for (i, c) in constraints.iter().enumerate() {
+            let mut row = vec![BigRational::zero(); cols + 1];
+            row[..vars].clone_from_slice(&c.coefficients);
+            row[cols] = c.rhs.clone();
+            match c.relation {
+                Relation::Le => row[slack] = BigRational::one(),
+                Relation::Ge => row[slack] = -BigRational::one(),
+                Relation::Eq => {}
+            }
+            if c.relation != Relation::Eq {
+                slack += 1;
+            }
+            if row[cols].is_negative() {
+                row.iter_mut().for_each(|v| *v = -v.clone());
+            }
+            row[artificial + i] = BigRational::one();
+            rows.push(row);
+        }
+
+        // phase 1: minimise the sum of the artificial variables
+        let mut costs = vec![BigRational::zero(); cols + 1];
+        for row in &rows {
+            for (j, v) in row.iter().enumerate() {
+                if !(artificial..cols).contains(&j) {
+                    costs[j] -= v;
+                }
+            }
+        }
+        let basis = (artificial..cols).collect();
+        let mut tableau = Tableau {
+            rows,
+            costs,
+            basis,
+            cols,
+        };
+        tableau.optimize(cols);
+        if !tableau.costs[cols].is_zero() {
+            return LpOutcome::Infeasible;
+        }
+        // drive the artificial variables left at 0 out of the basis, dropping redundant rows
+        let mut row = 0;
+        while row < tableau.rows.len() {
+            if tableau.basis[row] < artificial {
+                row += 1;
+                continue;
+            }
+            match (0..artificial).find(|&j| !tableau.rows[row][j].is_zero()) {
+                Some(col) => {
+                    tableau.pivot(row, col);
+                    row += 1;
+                }
+                None => {
+                    tableau.rows.remove(row);
+                    tableau.basis.remove(row);
+                }
+            }
+        }
+
+        // phase 2: minimise the objective without the artificial variables
+        tableau.costs = vec![BigRational::zero(); cols + 1];
+        tableau.costs[..vars].clone_from_slice(objective);
+        for row in 0..tableau.rows.len() {
+            let factor = tableau.costs[tableau.basis[row]].clone();
+            if !factor.is_zero() {
+                for (v, r) in tableau.costs.iter_mut().zip(&tableau.rows[row]) {
+                    *v -= &factor * r;
+                }
+            }
+        }
+        if !tableau.optimize(artificial) {
+            return LpOutcome::Unbounded;
+        }
+        let mut values = vec![BigRational::zero(); vars];
+        for (row, &col) in tableau.basis.iter().enumerate() {
+            if col < vars {
+                values[col] = tableau.rows[row][cols].clone();
+            }
+        }
+        LpOutcome::Optimal {
+            values,
+            objective: -tableau.costs[cols].clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use num_rational::BigRational;
+    use proptest::prelude::*;
+
+    use super::{IntegerProgram, IntegerSolution, LpOutcome, Relation};
+
+    #[test]
+    fn test_relaxation() {
+        // max x + y st 2x + y <= 4, x + 2y <= 4
+        let mut program = IntegerProgram::new(2);
+        program.constrain(&[2, 1], Relation::Le, 4).unwrap();
+        program.constrain(&[1, 2], Relation::Le, 4).unwrap();
+        program.minimize(&[-1, -1]).unwrap();
+        let q = |n, d| BigRational::new(BigInt::from(n), BigInt::from(d));
+        assert_eq!(
+            LpOutcome::Optimal {
+                values: vec![q(4, 3), q(4, 3)],
+                objective: q(-8, 3)
+            },
+            program.solve_relaxation()
+        );
+        assert_eq!(-2, program.solve().unwrap().unwrap().objective);
+
+        program.constrain(&[1, 1], Relation::Ge, 5).unwrap();
+        assert_eq!(LpOutcome::Infeasible, program.solve_relaxation());
+        assert_eq!(None, program.solve().unwrap());
+
+        let mut unbounded = IntegerProgram::new(2);
+        unbounded.constrain(&[1, -1], Relation::Eq, 1).unwrap();
+        unbounded.minimize(&[-1, 0]).unwrap();
+        assert_eq!(LpOutcome::Unbounded, unbounded.solve_relaxation());
+        assert!(unbounded.solve().is_err());
+
+        // min x + y st 2x - 2y = 1: bounded relaxation, no integer point and no end to the
+        // search without bounds on the variables
+        let mut odd = IntegerProgram::new(2);
+        odd.constrain(&[2, -2], Relation::Eq, 1).unwrap();
+        odd.minimize(&[1, 1]).unwrap();
+        assert!(matches!(odd.solve_relaxation(), LpOutcome::Optimal { .. }));
+        assert!(odd.solve().is_err());
+        odd.constrain(&[1, 0], Relation::Le, 10).unwrap();
+        assert_eq!(None, odd.solve().unwrap());
+    }
+
+    #[test]
+    fn test_equalities() {
+        // buttons (3) (1,3) (2) (2,3) (0,2) (0,1) reaching the counters {3,5,4,7}
+        let buttons: [&[usize]; 6] = [&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]];
+        let counters = [3, 5, 4, 7];
+        let mut program = IntegerProgram::new(buttons.len());
+        for (counter, &target) in counters.iter().enumerate() {
+            let coefficients = buttons
+                .iter()
+                .map(|b| b.contains(&counter) as i64)
+                .collect::<Vec<_>>();
+            program
+                .constrain(&coefficients, Relation::Eq, target)
+                .unwrap();
+        }
+        program.minimize(&[1; 6]).unwrap();
+        let IntegerSolution { values, objective } = program.solve().unwrap().unwrap();
+        assert_eq!(10, objective);
+        for (counter, &target) in counters.iter().enumerate() {
+            let reached: i64 = (0..buttons.len())
+                .filter(|&b| buttons[b].contains(&counter))
+                .map(|b| values[b])
+                .sum();
+            assert_eq!(target, reached);
+        }
+    }
+
+    /// Smallest objective over every assignment with values up to `max`.
+    fn brute_force(rows: &[(Vec<i64>, i64)], objective: &[i64], max: i64) -> Option<i64> {
+        let vars = objective.len();
+        let mut values = vec![0; vars];
+        let mut best = None;
+        loop {
+            let dot = |c: &[i64]| c.iter().zip(&values).map(|(a, b)| a * b).sum::<i64>();
+            if rows.iter().all(|(c, rhs)| dot(c) == *rhs) {
+                let value = dot(objective);
+                best = Some(best.map_or(value, |b: i64| b.min(value)));
+            }
+            let Some(i) = values.iter().position(|&v| v < max) else {
+                return best;
+            };
+            values[..i].iter_mut().for_each(|v| *v = 0);
+            values[i] += 1;
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn prop_matches_brute_force(
+            rows in prop::collection::vec((prop::collection::vec(0..3i64, 3), 0..12i64), 1..4),
+            objective in prop::collection::vec(0..4i64, 3),
+        ) {
+            // non-negative coefficients with every variable in a row keep values below 12
+            let mut rows = rows;
+            rows.push((vec![1, 1, 1], rows[0].1));
+            let mut program = IntegerProgram::new(3);
+            for (coefficients, rhs) in &rows {
+                program.constrain(coefficients, Relation::Eq, *rhs).unwrap();
+            }
+            program.minimize(&objective).unwrap();
+            let solution = program.solve().unwrap();
+            prop_assert_eq!(
+                brute_force(&rows, &objective, 12),
+                solution.map(|s| s.objective)
+            );
+        }
+    }
+}
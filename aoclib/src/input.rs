@@ -0,0 +1,308 @@
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::str;
+
+use anyhow::{bail, Context, Result};
+use memmap2::Mmap;
+
+use crate::crypt;
+
+/// Where the puzzle input of a day is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// `inputs/{day}_{name}.txt` in the crate's inputs directory.
+    Variant(String),
+    /// Any file on disk.
+    Path(PathBuf),
+    /// Standard input, selected with `-`.
+    Stdin,
+}
+
+impl Default for InputSource {
+    fn default() -> Self {
+        InputSource::Variant("1".to_owned())
+    }
+}
+
+impl InputSource {
+    /// Interprets a command line argument: `-` is stdin, anything that looks like a file is
+    /// read as is, everything else names a variant (`2`, `_2`, `example`, ...).
+    pub fn from_arg(arg: &str) -> Self {
+        if arg == "-" {
+            return InputSource::Stdin;
+        }
+        let path = Path::new(arg);
+        if path.is_file() || arg.contains(['/', '\\']) || arg.ends_with(".txt") {
+            return InputSource::Path(path.to_owned());
+        }
+        InputSource::Variant(arg.trim_start_matches('_').to_owned())
+    }
+
+    /// Source selected by the first command line argument that isn't a `--` option, if any.
+    pub fn from_args() -> Option<Self> {
+        env::args()
+            .skip(1)
+            .find(|arg| !arg.starts_with("--"))
+            .map(|arg| Self::from_arg(&arg))
+    }
+
+    /// Name identifying this input of `day` in the answers file, e.g. `d05_1`.
+    pub fn name(&self, day: &str) -> String {
+        match self {
+            InputSource::Variant(variant) => format!("{day}_{variant}"),
+            InputSource::Path(path) => path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string()),
+            InputSource::Stdin => "stdin".to_owned(),
+        }
+    }
+}
+
+/// Finds the `inputs/` directory of a crate: `$AOC_INPUTS_DIR` when set, then the one next to
+/// the crate manifest, then the one in the current directory.
+pub fn inputs_dir(manifest_dir: &str) -> Result<PathBuf> {
+    if let Some(dir) = env::var_os("AOC_INPUTS_DIR") {
+        let dir = PathBuf::from(dir);
+        if !dir.is_dir() {
+            bail!("AOC_INPUTS_DIR={} is not a directory", dir.display());
+        }
+        return Ok(dir);
+    }
+    [
+        Path::new(manifest_dir).join("inputs"),
+        PathBuf::from("inputs"),
+    ]
+    .into_iter()
+    .find(|dir| dir.is_dir())
+    .with_context(|| {
+        format!("no inputs directory found in {manifest_dir} or the current directory")
+    })
+}
+
+/// Names of the files in `dir` belonging to `day`, sorted.
+pub fn available_inputs(dir: &Path, day: &str) -> Vec<String> {
+    let prefix = format!("{day}_");
+    let mut names = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|name| name.starts_with(&prefix))
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+/// Every `inputs/{day}_*.txt` input of a crate, plain or encrypted, `_1` first.
+pub fn day_inputs(manifest_dir: &str, day: &str) -> Result<Vec<InputSource>> {
+    let dir = inputs_dir(manifest_dir)?;
+    let prefix = format!("{day}_");
+    let mut variants = available_inputs(&dir, day)
+        .into_iter()
+        .filter_map(|name| {
+            let name = name
+                .strip_suffix(&format!(".{}", crypt::EXTENSION))
+                .unwrap_or(&name);
+            Some(name.strip_prefix(&prefix)?.strip_suffix(".txt")?.to_owned())
+        })
+        .collect::<Vec<_>>();
+    if variants.is_empty() {
+        bail!("no input for {day} in {}", dir.display());
+    }
+    variants.sort_by_key(|v| (v.parse::<u32>().unwrap_or(u32::MAX), v.clone()));
+    variants.dedup();
+    Ok(variants.into_iter().map(InputSource::Variant).collect())
+}
+
+/// Contents of an input. Plain files are memory-mapped instead of copied into a `String`, so
+/// that huge inputs are only paged in as the solution reads them.
+pub enum Input {
+    Owned(String),
+    Mapped(Mmap),
+}
+
+impl Input {
+    fn map(path: &Path) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("failed to read input file {}", path.display()))?;
+        if file.metadata()?.len() == 0 {
+            return Ok(Input::Owned(String::new()));
+        }
+        // SAFETY: inputs are only ever read, nothing modifies the file while it is mapped.
+        let mmap = unsafe { Mmap::map(&file) }
+            .with_context(|| format!("failed to map input file {}", path.display()))?;
+        str::from_utf8(&mmap).with_context(|| format!("{} is not valid UTF-8", path.display()))?;
+        Ok(Input::Mapped(mmap))
+    }
+}
+
+impl Deref for Input {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            Input::Owned(file) => file,
+            // SAFETY: checked to be valid UTF-8 when mapped.
+            Input::Mapped(mmap) => unsafe { str::from_utf8_unchecked(mmap) },
+        }
+    }
+}
+
+impl std::fmt::Debug for Input {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// File holding the input of `day` from `source`, plain or encrypted. `None` for stdin.
+pub fn input_path(manifest_dir: &str, day: &str, source: &InputSource) -> Result<Option<PathBuf>> {
+    let variant = match source {
+        InputSource::Stdin => return Ok(None),
+        InputSource::Path(path) => return Ok(Some(path.clone())),
+        InputSource::Variant(variant) => variant,
+    };
+    let dir = inputs_dir(manifest_dir)?;
+    let path = dir.join(format!("{day}_{variant}.txt"));
+    if path.is_file() {
+        return Ok(Some(path));
+    }
+    let encrypted = crypt::encrypted_path(&path);
+    if encrypted.is_file() {
+        return Ok(Some(encrypted));
+    }
+    let available = available_inputs(&dir, day);
+    if available.is_empty() {
+        bail!("no input for {day} in {}", dir.display());
+    }
+    bail!(
+        "no input {day}_{variant}.txt in {}, available: {}",
+        dir.display(),
+        available.join(", ")
+    );
+}
+
+/// Reads the input of `day` from `source`.
+pub fn read_input(manifest_dir: &str, day: &str, source: &InputSource) -> Result<Input> {
+    match input_path(manifest_dir, day, source)? {
+        Some(path) => read_file(&path),
+        None => {
+            let mut file = String::new();
+            io::stdin()
+                .read_to_string(&mut file)
+                .context("failed to read input from stdin")?;
+            Ok(Input::Owned(file))
+        }
+    }
+}
+
+/// Reads an input file, decrypting it when it has the `.enc` extension.
+pub fn read_file(path: &Path) -> Result<Input> {
+    if path.extension().is_some_and(|ext| ext == crypt::EXTENSION) {
+        return crypt::read_encrypted(path).map(Input::Owned);
+    }
+    Input::map(path)
+}
+
+/// Opens an input file as a stream. Encrypted inputs are decrypted in memory.
+pub fn open_file(path: &Path) -> Result<Box<dyn BufRead>> {
+    if path.extension().is_some_and(|ext| ext == crypt::EXTENSION) {
+        let file = crypt::read_encrypted(path)?;
+        return Ok(Box::new(Cursor::new(file.into_bytes())));
+    }
+    let file = File::open(path)
+        .with_context(|| format!("failed to read input file {}", path.display()))?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::{available_inputs, day_inputs, read_input, InputSource};
+    use crate::crypt::InputKey;
+
+    fn temp_crate(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoclib-{name}-{}", std::process::id()));
+        fs::create_dir_all(dir.join("inputs")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_from_arg() {
+        assert_eq!(InputSource::Stdin, InputSource::from_arg("-"));
+        assert_eq!(
+            InputSource::Variant("2".to_owned()),
+            InputSource::from_arg("_2")
+        );
+        assert_eq!(
+            InputSource::Variant("example".to_owned()),
+            InputSource::from_arg("example")
+        );
+        assert_eq!(
+            InputSource::Path(PathBuf::from("other/d01.txt")),
+            InputSource::from_arg("other/d01.txt")
+        );
+    }
+
+    #[test]
+    fn test_read_variant() {
+        let dir = temp_crate("variant");
+        fs::write(dir.join("inputs/d01_1.txt"), "1\n").unwrap();
+        fs::write(dir.join("inputs/d01_example.txt"), "2\n").unwrap();
+        fs::write(dir.join("inputs/d02_1.txt"), "3\n").unwrap();
+        let manifest_dir = dir.to_str().unwrap();
+
+        let default = read_input(manifest_dir, "d01", &InputSource::default()).unwrap();
+        assert_eq!("1\n", &*default);
+        let example = read_input(manifest_dir, "d01", &InputSource::from_arg("_example")).unwrap();
+        assert_eq!("2\n", &*example);
+        assert_eq!(
+            vec!["d01_1.txt", "d01_example.txt"],
+            available_inputs(&dir.join("inputs"), "d01")
+        );
+
+        assert_eq!(
+            vec![
+                InputSource::Variant("1".to_owned()),
+                InputSource::Variant("example".to_owned())
+            ],
+            day_inputs(manifest_dir, "d01").unwrap()
+        );
+        assert_eq!("d01_example", InputSource::from_arg("example").name("d01"));
+
+        let err = read_input(manifest_dir, "d01", &InputSource::from_arg("3")).unwrap_err();
+        assert!(err
+            .to_string()
+            .ends_with("available: d01_1.txt, d01_example.txt"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_read_encrypted() {
+        let dir = temp_crate("encrypted");
+        fs::write(dir.join(".aoc-key"), "secret\n").unwrap();
+        let encrypted = InputKey::from_passphrase("secret").encrypt(b"42\n");
+        fs::write(dir.join("inputs/d01_1.txt.enc"), encrypted).unwrap();
+        fs::write(dir.join("inputs/d01_2.txt"), "43\n").unwrap();
+        let manifest_dir = dir.to_str().unwrap();
+
+        assert_eq!(
+            vec![
+                InputSource::Variant("1".to_owned()),
+                InputSource::Variant("2".to_owned())
+            ],
+            day_inputs(manifest_dir, "d01").unwrap()
+        );
+        let file = read_input(manifest_dir, "d01", &InputSource::default()).unwrap();
+        assert_eq!("42\n", &*file);
+
+        fs::remove_file(dir.join(".aoc-key")).unwrap();
+        let err = read_input(manifest_dir, "d01", &InputSource::default()).unwrap_err();
+        assert!(format!("{err:#}").contains("no key to decrypt the inputs"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use sha2::{Digest, Sha256};
+
+/// Hex-encoded SHA-256 of an input.
+pub fn hash(file: &str) -> String {
+    let mut checker = Checker::default();
+    checker.update(file.as_bytes());
+    checker.hash()
+}
+
+/// Problems commonly caused by saving or pasting an input badly.
+pub fn corruption_warnings(file: &str) -> Vec<&'static str> {
+    let mut checker = Checker::default();
+    checker.update(file.as_bytes());
+    checker.warnings()
+}
+
+/// Hashes an input and looks for signs of corruption as it is fed chunk by chunk, so that
+/// streamed inputs can be checked without holding them in memory.
+#[derive(Default, Clone)]
+pub struct Checker {
+    hasher: Sha256,
+    head: Vec<u8>,
+    last: Option<u8>,
+    crlf: bool,
+}
+
+impl Checker {
+    const HEAD_LEN: usize = 32;
+
+    pub fn update(&mut self, data: &[u8]) {
+        let Some(&last) = data.last() else {
+            return;
+        };
+        self.hasher.update(data);
+        if self.head.len() < Self::HEAD_LEN {
+            let missing = Self::HEAD_LEN - self.head.len();
+            self.head.extend(&data[..missing.min(data.len())]);
+        }
+        self.crlf |=
+            (self.last == Some(b'\r') && data[0] == b'\n') || data.windows(2).any(|w| w == b"\r\n");
+        self.last = Some(last);
+    }
+
+    /// Feeds the whole content of `reader`.
+    pub fn update_from(&mut self, mut reader: impl Read) -> io::Result<()> {
+        let mut buf = vec![0; 1 << 16];
+        loop {
+            match reader.read(&mut buf)? {
+                0 => return Ok(()),
+                n => self.update(&buf[..n]),
+            }
+        }
+    }
+
+    /// Hex-encoded SHA-256 of the data fed so far.
+    pub fn hash(&self) -> String {
+        self.hasher
+            .clone()
+            .finalize()
+            .iter()
+            .fold(String::with_capacity(64), |mut hex, b| {
+                let _ = write!(hex, "{b:02x}");
+                hex
+            })
+    }
+
+    /// Problems commonly caused by saving or pasting an input badly.
+    pub fn warnings(&self) -> Vec<&'static str> {
+        let mut warnings = Vec::new();
+        let Some(last) = self.last else {
+            warnings.push("is empty");
+            return warnings;
+        };
+        let head = self.head.strip_prefix("\u{feff}".as_bytes());
+        if head.is_some() {
+            warnings.push("starts with a byte order mark");
+        }
+        if self.crlf {
+            warnings.push("has CRLF line endings");
+        }
+        if last != b'\n' {
+            warnings.push("doesn't end with a newline, its last line may be truncated");
+        }
+        let head = String::from_utf8_lossy(head.unwrap_or(&self.head)).to_ascii_lowercase();
+        let head = head.trim_start();
+        if head.starts_with("<!doctype html") || head.starts_with("<html") {
+            warnings.push("is an HTML page, not a puzzle input");
+        }
+        warnings
+    }
+}
+
+/// Recorded hashes of the inputs of a crate, kept in `inputs.sha256` next to its `inputs/`
+/// directory in the format of `sha256sum` (`<hash>  <file name>`). Encrypted inputs are
+/// recorded under their clear name and hash.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    by_name: BTreeMap<String, String>,
+}
+
+impl Manifest {
+    pub fn path(inputs_dir: &Path) -> PathBuf {
+        inputs_dir
+            .parent()
+            .unwrap_or(Path::new("."))
+            .join("inputs.sha256")
+    }
+
+    /// Loads the manifest of `inputs_dir`, empty when there is none.
+    pub fn load(inputs_dir: &Path) -> Result<Self> {
+        let path = Self::path(inputs_dir);
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?
+            .parse()
+            .with_context(|| format!("invalid manifest {}", path.display()))
+    }
+
+    pub fn save(&self, inputs_dir: &Path) -> Result<()> {
+        let path = Self::path(inputs_dir);
+        fs::write(&path, self.to_string())
+            .with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    pub fn insert(&mut self, name: &str, file: &str) {
+        self.by_name.insert(name.to_owned(), hash(file));
+    }
+
+    /// Warning when `hash` isn't the one recorded for `name`.
+    pub fn check(&self, name: &str, hash: &str) -> Option<String> {
+        let expected = self.by_name.get(name)?;
+        (expected != hash).then(|| format!("doesn't match its hash in inputs.sha256 ({expected})"))
+    }
+}
+
+impl FromStr for Manifest {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut by_name = BTreeMap::new();
+        for (i, l) in s.lines().enumerate() {
+            if l.trim().is_empty() {
+                continue;
+            }
+            let Some((hash, name)) = l.split_once(' ') else {
+                bail!("line {}: expected `<hash>  <file name>`", i + 1);
+            };
+            let name = name.trim_start().trim_start_matches('*');
+            by_name.insert(name.to_owned(), hash.to_owned());
+        }
+        Ok(Self { by_name })
+    }
+}
+
+impl std::fmt::Display for Manifest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, hash) in self.by_name.iter() {
+            writeln!(f, "{hash}  {name}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{corruption_warnings, hash, Checker, Manifest};
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            hash("")
+        );
+    }
+
+    #[test]
+    fn test_corruption_warnings() {
+        assert!(corruption_warnings("1 2\n3 4\n").is_empty());
+        assert_eq!(vec!["is empty"], corruption_warnings(""));
+        assert_eq!(
+            vec!["doesn't end with a newline, its last line may be truncated"],
+            corruption_warnings("1 2\n3")
+        );
+        assert_eq!(
+            vec!["has CRLF line endings"],
+            corruption_warnings("1 2\r\n3 4\r\n")
+        );
+        assert_eq!(
+            vec!["starts with a byte order mark"],
+            corruption_warnings("\u{feff}1 2\n")
+        );
+        assert_eq!(
+            vec!["is an HTML page, not a puzzle input"],
+            corruption_warnings("\n<!DOCTYPE html>\n<html lang=\"en-us\">\n</html>\n")
+        );
+    }
+
+    #[test]
+    fn test_checker_chunks() {
+        let file = "\u{feff}1 2\r\n3";
+        let mut checker = Checker::default();
+        for chunk in file.as_bytes().chunks(1) {
+            checker.update(chunk);
+        }
+        assert_eq!(hash(file), checker.hash());
+        assert_eq!(corruption_warnings(file), checker.warnings());
+        assert_eq!(3, checker.warnings().len());
+    }
+
+    #[test]
+    fn test_manifest() {
+        let mut manifest = Manifest::default();
+        manifest.insert("d01_1.txt", "1 2\n");
+        let manifest: Manifest = manifest.to_string().parse().unwrap();
+        assert_eq!(None, manifest.check("d01_1.txt", &hash("1 2\n")));
+        assert_eq!(None, manifest.check("d01_2.txt", &hash("1 2\n")));
+        assert!(manifest.check("d01_1.txt", &hash("1 2")).is_some());
+    }
+}
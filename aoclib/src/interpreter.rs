@@ -0,0 +1,316 @@
+use std::io::{BufReader, Read};
+
+use anyhow::{ensure, Result};
+
+/// Runs an instruction on the state of the machine with its arguments.
+type Handler<S> = Box<dyn Fn(&mut S, &[u64])>;
+
+struct Opcode<S> {
+    name: String,
+    arity: usize,
+    handler: Handler<S>,
+}
+
+/// Instruction found in memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction<'a> {
+    /// Byte offset of its first byte.
+    pub offset: usize,
+    pub name: &'a str,
+    pub args: Vec<u64>,
+}
+
+/// Interpreter of the instructions hidden in corrupted memory, as in 2024 d03: `name(a,b)`
+/// with as many unsigned decimal arguments of 1 to `max_digits` digits as the opcode takes,
+/// anything else being skipped. Instructions are run in order on a state `S`.
+pub struct Interpreter<S> {
+    opcodes: Vec<Opcode<S>>,
+    max_digits: usize,
+}
+
+/// Instruction being read, started at `offset`.
+#[derive(Debug, Clone)]
+struct Partial {
+    opcode: usize,
+    offset: usize,
+    /// Bytes of `name(` read.
+    matched: usize,
+    args: Vec<u64>,
+    /// Digits of the last argument, 0 before it starts.
+    digits: usize,
+}
+
+enum Step {
+    Continue(Partial),
+    Done(Partial),
+    Fail,
+}
+
+impl<S> Default for Interpreter<S> {
+    fn default() -> Self {
+        Self {
+            opcodes: Vec::new(),
+            max_digits: 3,
+        }
+    }
+}
+
+impl<S> Interpreter<S> {
+    /// Interpreter without any opcode, with arguments of at most 3 digits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the opcode `name` taking `arity` arguments, run by `handler`.
+    pub fn register(
+        &mut self,
+        name: &str,
+        arity: usize,
+        handler: impl Fn(&mut S, &[u64]) + 'static,
+    ) -> Result<&mut Self> {
+        ensure!(!name.is_empty(), "empty opcode name");
+        ensure!(
+            !name.bytes().any(|b| b == b'('),
+            "opcode name {name:?} has a parenthesis"
+        );
+        // an instruction could otherwise start within the arguments of another one, which
+        // is dropped when the inner one ends first
+        ensure!(
+            !name.starts_with(|c: char| c.is_ascii_digit() || c == ',' || c == ')'),
+            "opcode name {name:?} starts like arguments"
+        );
+        self.opcodes.push(Opcode {
+            name: name.to_owned(),
+            arity,
+            handler: Box::new(handler),
+        });
+        Ok(self)
+    }
+
+    /// Sets the maximum number of digits of an argument, at most 19 so that it fits a `u64`.
+    pub fn max_digits(&mut self, max_digits: usize) -> Result<&mut Self> {
+        ensure!(
+            (1..=19).contains(&max_digits),
+            "arguments of {max_digits} digits"
+        );
+        self.max_digits = max_digits;
+        Ok(self)
+    }
+
+    /// Reads `byte` for the instruction `partial`.
+    fn step(&self, mut partial: Partial, byte: u8) -> Step {
+        let opcode = &self.opcodes[partial.opcode];
+        let name = opcode.name.as_bytes();
+        let args_full = partial.args.len() == opcode.arity;
+        if partial.matched < name.len() {
+            if byte != name[partial.matched] {
+                return Step::Fail;
+            }
+            partial.matched += 1;
+            return Step::Continue(partial);
+        }
+        if partial.matched == name.len() {
+            if byte != b'(' {
+                return Step::Fail;
+            }
+            partial.matched += 1;
+            return Step::Continue(partial);
+        }
+        match byte {
+            b'0'..=b'9' if partial.digits == 0 && !args_full => {
+                partial.args.push(u64::from(byte - b'0'));
+                partial.digits = 1;
+            }
+            b'0'..=b'9' if partial.digits > 0 && partial.digits < self.max_digits => {
+                let arg = partial.args.last_mut().expect("an argument is being read");
+                *arg = *arg * 10 + u64::from(byte - b'0');
+                partial.digits += 1;
+            }
+            b',' if partial.digits > 0 && !args_full => partial.digits = 0,
+            b')' if args_full && (partial.digits > 0 || opcode.arity == 0) => {
+                return Step::Done(partial)
+            }
+            _ => return Step::Fail,
+        }
+        Step::Continue(partial)
+    }
+
+    /// Reads `memory` in a single pass, calling `on_instruction` with each instruction found and
+    /// its opcode. The instructions being read are followed at once, so the first one to end is
+    /// kept and the others, which started before it, are dropped.
+    fn scan(
+        &self,
+        memory: impl Read,
+        mut on_instruction: impl FnMut(&Opcode<S>, Instruction),
+    ) -> Result<()> {
+        let mut partials = Vec::new();
+        for (offset, byte) in BufReader::new(memory).bytes().enumerate() {
+            let byte = byte?;
+            let mut done = None;
+            for partial in std::mem::take(&mut partials) {
+                match self.step(partial, byte) {
+                    Step::Continue(partial) => partials.push(partial),
+                    Step::Done(partial) => {
+                        done = Some(partial);
+                        break;
+                    }
+                    Step::Fail => {}
+                }
+            }
+            if let Some(partial) = done {
+                partials.clear();
+                let opcode = &self.opcodes[partial.opcode];
+                on_instruction(
+                    opcode,
+                    Instruction {
+                        offset: partial.offset,
+                        name: &opcode.name,
+                        args: partial.args,
+                    },
+                );
+                continue;
+            }
+            for (i, opcode) in self.opcodes.iter().enumerate() {
+                if opcode.name.as_bytes()[0] == byte {
+                    partials.push(Partial {
+                        opcode: i,
+                        offset,
+                        matched: 1,
+                        args: Vec::new(),
+                        digits: 0,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs every instruction of `memory` on `state`.
+    pub fn run(&self, memory: impl Read, state: &mut S) -> Result<()> {
+        self.run_traced(memory, state, |_| {})
+    }
+
+    /// Same as [`Interpreter::run`], calling `trace` with each instruction before running it.
+    pub fn run_traced(
+        &self,
+        memory: impl Read,
+        state: &mut S,
+        mut trace: impl FnMut(&Instruction),
+    ) -> Result<()> {
+        self.scan(memory, |opcode, instruction| {
+            trace(&instruction);
+            (opcode.handler)(state, &instruction.args);
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::Interpreter;
+
+    #[derive(Default)]
+    struct State {
+        sum: u64,
+        disabled: bool,
+    }
+
+    fn interpreter() -> Interpreter<State> {
+        let mut interpreter = Interpreter::new();
+        interpreter
+            .register("mul", 2, |s: &mut State, args| {
+                if !s.disabled {
+                    s.sum += args[0] * args[1]
+                }
+            })
+            .unwrap()
+            .register("do", 0, |s, _| s.disabled = false)
+            .unwrap()
+            .register("don't", 0, |s, _| s.disabled = true)
+            .unwrap();
+        interpreter
+    }
+
+    #[test]
+    fn test_run() {
+        let memory = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        let mut state = State::default();
+        let mut trace = Vec::new();
+        interpreter()
+            .run_traced(memory.as_bytes(), &mut state, |i| {
+                trace.push((i.offset, i.name.to_owned(), i.args.clone()))
+            })
+            .unwrap();
+        assert_eq!(48, state.sum);
+        let expected = [
+            (1, "mul", vec![2, 4]),
+            (20, "don't", vec![]),
+            (28, "mul", vec![5, 5]),
+            (48, "mul", vec![11, 8]),
+            (59, "do", vec![]),
+            (64, "mul", vec![8, 5]),
+        ]
+        .map(|(offset, name, args)| (offset, name.to_owned(), args));
+        assert_eq!(expected.to_vec(), trace);
+
+        let mut state = State::default();
+        let memory = "mul(1234,2)mul(1,2,3)mul(,2)mul(4,)mumul(3,3)mul(0,7)do(1)don't()mul(1,1)";
+        interpreter().run(memory.as_bytes(), &mut state).unwrap();
+        assert_eq!(9, state.sum);
+        let mut wide = interpreter();
+        wide.max_digits(4).unwrap();
+        let mut state = State::default();
+        wide.run("mul(1234,2)".as_bytes(), &mut state).unwrap();
+        assert_eq!(2468, state.sum);
+        assert!(Interpreter::<State>::new()
+            .register("", 1, |_, _| {})
+            .is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_run(memory in "(mul\\([0-9]{1,4},[0-9]{1,4}\\)|do\\(\\)|don't\\(\\)|[mul(,)don't0-9x]){0,40}") {
+            let mut state = State::default();
+            interpreter().run(memory.as_bytes(), &mut state).unwrap();
+            prop_assert_eq!(naive_sum(&memory), state.sum);
+        }
+    }
+
+    /// Sum of the enabled products, trying to read an instruction at each byte.
+    fn naive_sum(memory: &str) -> u64 {
+        let bytes = memory.as_bytes();
+        let (mut sum, mut enabled, mut i) = (0, true, 0);
+        let number = |j: &mut usize| {
+            let start = *j;
+            while *j < bytes.len() && *j - start < 3 && bytes[*j].is_ascii_digit() {
+                *j += 1;
+            }
+            (*j > start).then(|| memory[start..*j].parse::<u64>().unwrap())
+        };
+        while i < bytes.len() {
+            let rest = &memory[i..];
+            if rest.starts_with("do()") {
+                (enabled, i) = (true, i + 4);
+            } else if rest.starts_with("don't()") {
+                (enabled, i) = (false, i + 7);
+            } else if rest.starts_with("mul(") {
+                let mut j = i + 4;
+                let a = number(&mut j);
+                let comma = j < bytes.len() && bytes[j] == b',';
+                j += 1;
+                let b = if comma { number(&mut j) } else { None };
+                match (a, b) {
+                    (Some(a), Some(b)) if j < bytes.len() && bytes[j] == b')' => {
+                        sum += if enabled { a * b } else { 0 };
+                        i = j + 1;
+                    }
+                    _ => i += 1,
+                }
+            } else {
+                i += 1;
+            }
+        }
+        sum
+    }
+}
@@ -0,0 +1,373 @@
+use std::fmt::Debug;
+use std::ops::{Bound, RangeBounds, RangeInclusive};
+
+/// Primitive integer usable as an interval bound.
+pub trait Int: Copy + Ord + Debug {
+    const MIN: Self;
+    const MAX: Self;
+
+    /// `self + 1`, `None` on overflow.
+    fn succ(self) -> Option<Self>;
+    /// `self - 1`, `None` on overflow.
+    fn pred(self) -> Option<Self>;
+    /// Number of integers in `lo..=hi`, `lo <= hi`.
+    fn count(lo: Self, hi: Self) -> u128;
+    fn to_i128(self) -> i128;
+    /// `None` when `value` doesn't fit.
+    fn from_i128(value: i128) -> Option<Self>;
+}
+
+macro_rules! impl_int {
+    ($($t:ty),*) => {
+        $(
+            impl Int for $t {
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn succ(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn pred(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn count(lo: Self, hi: Self) -> u128 {
+                    (hi as i128).abs_diff(lo as i128) + 1
+                }
+
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+
+                fn from_i128(value: i128) -> Option<Self> {
+                    Self::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// Inclusive bounds of `range`, `None` when it is empty.
+pub(crate) fn inclusive_bounds<T: Int>(range: impl RangeBounds<T>) -> Option<(T, T)> {
+    let lo = match range.start_bound() {
+        Bound::Included(&lo) => lo,
+        Bound::Excluded(&lo) => lo.succ()?,
+        Bound::Unbounded => T::MIN,
+    };
+    let hi = match range.end_bound() {
+        Bound::Included(&hi) => hi,
+        Bound::Excluded(&hi) => hi.pred()?,
+        Bound::Unbounded => T::MAX,
+    };
+    (lo <= hi).then_some((lo, hi))
+}
+
+/// Set of integers stored as sorted, disjoint and non-adjacent inclusive ranges. Ranges can be
+/// given in any form (`a..b`, `a..=b`, `a..`, `..`) and are merged on insertion.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+impl<T: Int> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a set from ranges sorted by their start, merging the ones that overlap or touch.
+    fn from_sorted(sorted: impl IntoIterator<Item = (T, T)>) -> Self {
+        let mut ranges: Vec<(T, T)> = Vec::new();
+        for (lo, hi) in sorted {
+            match ranges.last_mut() {
+                Some(last) if last.1.succ().is_none_or(|next| lo <= next) => {
+                    last.1 = last.1.max(hi);
+                }
+                _ => ranges.push((lo, hi)),
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Adds every integer of `range`, merging it with the ranges it overlaps or touches.
+    pub fn insert(&mut self, range: impl RangeBounds<T>) {
+        let Some((lo, hi)) = inclusive_bounds(range) else {
+            return;
+        };
+        let start = self
+            .ranges
+            .partition_point(|r| r.1.succ().is_some_and(|next| next < lo));
+        let end = self
+            .ranges
+            .partition_point(|r| r.0.pred().is_none_or(|prev| prev <= hi));
+        let merged = if start < end {
+            (lo.min(self.ranges[start].0), hi.max(self.ranges[end - 1].1))
+        } else {
+            (lo, hi)
+        };
+        self.ranges.splice(start..end, [merged]);
+    }
+
+    /// Removes every integer of `range`, splitting the ranges it cuts through.
+    pub fn remove(&mut self, range: impl RangeBounds<T>) {
+        let Some((lo, hi)) = inclusive_bounds(range) else {
+            return;
+        };
+        let start = self.ranges.partition_point(|r| r.1 < lo);
+        let end = self.ranges.partition_point(|r| r.0 <= hi);
+        if start == end {
+            return;
+        }
+        let (first, last) = (self.ranges[start], self.ranges[end - 1]);
+        let left = (first.0 < lo).then(|| (first.0, lo.pred().unwrap()));
+        let right = (last.1 > hi).then(|| (hi.succ().unwrap(), last.1));
+        self.ranges
+            .splice(start..end, left.into_iter().chain(right));
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|r| r.1 < value);
+        self.ranges.get(i).is_some_and(|r| r.0 <= value)
+    }
+
+    /// Whether every integer of `range` is in the set.
+    pub fn contains_range(&self, range: impl RangeBounds<T>) -> bool {
+        let Some((lo, hi)) = inclusive_bounds(range) else {
+            return true;
+        };
+        let i = self.ranges.partition_point(|r| r.1 < lo);
+        self.ranges.get(i).is_some_and(|r| r.0 <= lo && hi <= r.1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of integers in the set.
+    pub fn len(&self) -> u128 {
+        self.ranges.iter().map(|&(lo, hi)| T::count(lo, hi)).sum()
+    }
+
+    /// The ranges of the set, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|&(lo, hi)| lo..=hi)
+    }
+
+    /// The ranges missing between the first and the last integer of the set, in increasing
+    /// order.
+    pub fn gaps(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges
+            .windows(2)
+            .map(|w| w[0].1.succ().unwrap()..=w[1].0.pred().unwrap())
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut all = [&self.ranges[..], &other.ranges[..]].concat();
+        all.sort_unstable();
+        Self::from_sorted(all)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut i, mut j) = (0, 0);
+        let mut ranges = Vec::new();
+        while let (Some(&a), Some(&b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let (lo, hi) = (a.0.max(b.0), a.1.min(b.1));
+            if lo <= hi {
+                ranges.push((lo, hi));
+            }
+            if a.1 < b.1 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.intersection(&other.complement(..))
+    }
+
+    /// Integers of `bounds` that are not in the set.
+    pub fn complement(&self, bounds: impl RangeBounds<T>) -> Self {
+        let Some((lo, hi)) = inclusive_bounds(bounds) else {
+            return Self::new();
+        };
+        let mut ranges = Vec::new();
+        let mut next = Some(lo);
+        for &(start, end) in self.ranges.iter() {
+            let Some(from) = next.filter(|&from| from <= hi) else {
+                break;
+            };
+            if end < from {
+                continue;
+            }
+            if from < start {
+                ranges.push((from, start.pred().unwrap().min(hi)));
+            }
+            next = end.succ();
+        }
+        if let Some(from) = next.filter(|&from| from <= hi) {
+            ranges.push((from, hi));
+        }
+        Self { ranges }
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.iter().all(|r| other.contains_range(r))
+    }
+
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(other).is_empty()
+    }
+}
+
+impl<T: Int, R: RangeBounds<T>> FromIterator<R> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        let mut all = iter
+            .into_iter()
+            .filter_map(inclusive_bounds)
+            .collect::<Vec<_>>();
+        all.sort_unstable();
+        Self::from_sorted(all)
+    }
+}
+
+impl<T: Int, R: RangeBounds<T>> Extend<R> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = R>>(&mut self, iter: I) {
+        *self = self.union(&iter.into_iter().collect());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::IntervalSet;
+
+    #[test]
+    fn test_insert_merges() {
+        let mut set = IntervalSet::new();
+        set.insert(10..=14);
+        set.insert(3..6);
+        set.insert(16..=20);
+        set.insert(12..=18);
+        assert_eq!(vec![3..=5, 10..=20], set.iter().collect::<Vec<_>>());
+        set.insert(6..10);
+        assert_eq!(vec![3..=20], set.iter().collect::<Vec<_>>());
+        assert_eq!(18, set.len());
+        assert!(set.contains(3) && set.contains(20) && !set.contains(21));
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut set: IntervalSet<u8> = [250..].into_iter().collect();
+        set.insert(..=3);
+        assert_eq!(vec![0..=3, 250..=255], set.iter().collect::<Vec<_>>());
+        assert_eq!(vec![4..=249], set.gaps().collect::<Vec<_>>());
+        assert_eq!(set.complement(..), set.gaps().collect());
+        set.insert(..);
+        assert_eq!(256, set.len());
+        set.remove(..255);
+        assert_eq!(vec![255..=255], set.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_subset() {
+        let a: IntervalSet<u32> = [2..=8].into_iter().collect();
+        let b: IntervalSet<u32> = [3..=7].into_iter().collect();
+        let c: IntervalSet<u32> = [9..=10].into_iter().collect();
+        assert!(a.is_superset(&b) && b.is_subset(&a) && !a.is_subset(&b));
+        assert!(a.is_disjoint(&c) && !a.is_disjoint(&b));
+    }
+
+    const WIDTH: usize = 256;
+
+    fn bitmap(set: &IntervalSet<u8>) -> Vec<bool> {
+        (0..WIDTH).map(|v| set.contains(v as u8)).collect()
+    }
+
+    fn from_ranges(ranges: &[(u8, u8)]) -> (IntervalSet<u8>, Vec<bool>) {
+        let mut set = IntervalSet::new();
+        let mut bits = vec![false; WIDTH];
+        for &(a, b) in ranges {
+            if a % 2 == 0 {
+                set.insert(a..=b);
+                (a..=b).for_each(|v| bits[v as usize] = true);
+            } else {
+                set.insert(a..b);
+                (a..b).for_each(|v| bits[v as usize] = true);
+            }
+        }
+        (set, bits)
+    }
+
+    fn ranges() -> impl Strategy<Value = Vec<(u8, u8)>> {
+        prop::collection::vec((any::<u8>(), any::<u8>()), 0..8)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_insert(ranges in ranges()) {
+            let (set, bits) = from_ranges(&ranges);
+            prop_assert_eq!(&bits, &bitmap(&set));
+            prop_assert_eq!(bits.iter().filter(|&&b| b).count() as u128, set.len());
+            let normalized = set.iter().collect::<Vec<_>>();
+            prop_assert!(normalized.windows(2).all(|w| *w[0].end() as usize + 1 < *w[1].start() as usize));
+            let collected: IntervalSet<u8> = normalized.iter().cloned().collect();
+            prop_assert_eq!(&set, &collected);
+        }
+
+        #[test]
+        fn prop_remove(ranges in ranges(), lo: u8, hi: u8) {
+            let (mut set, mut bits) = from_ranges(&ranges);
+            set.remove(lo..=hi);
+            (lo..=hi).for_each(|v| bits[v as usize] = false);
+            prop_assert_eq!(bits, bitmap(&set));
+        }
+
+        #[test]
+        fn prop_set_operations(a in ranges(), b in ranges(), lo: u8, hi: u8) {
+            let (a, bits_a) = from_ranges(&a);
+            let (b, bits_b) = from_ranges(&b);
+            let zip = |f: fn(bool, bool) -> bool| {
+                bits_a.iter().zip(&bits_b).map(|(&x, &y)| f(x, y)).collect::<Vec<_>>()
+            };
+            prop_assert_eq!(zip(|x, y| x || y), bitmap(&a.union(&b)));
+            prop_assert_eq!(zip(|x, y| x && y), bitmap(&a.intersection(&b)));
+            prop_assert_eq!(zip(|x, y| x && !y), bitmap(&a.difference(&b)));
+            prop_assert_eq!(bits_a.iter().zip(&bits_b).all(|(&x, &y)| !x || y), a.is_subset(&b));
+            prop_assert_eq!(zip(|x, y| x && y).iter().all(|&z| !z), a.is_disjoint(&b));
+
+            let complement = (0..WIDTH)
+                .map(|v| (lo as usize..=hi as usize).contains(&v) && !bits_a[v])
+                .collect::<Vec<_>>();
+            prop_assert_eq!(complement, bitmap(&a.complement(lo..=hi)));
+        }
+
+        #[test]
+        fn prop_gaps(ranges in ranges()) {
+            let (set, bits) = from_ranges(&ranges);
+            let first = bits.iter().position(|&b| b);
+            let last = bits.iter().rposition(|&b| b);
+            let mut gaps = IntervalSet::new();
+            set.gaps().for_each(|g| gaps.insert(g));
+            let expected = (0..WIDTH)
+                .map(|v| first.zip(last).is_some_and(|(f, l)| f < v && v < l && !bits[v]))
+                .collect::<Vec<_>>();
+            prop_assert_eq!(expected, bitmap(&gaps));
+        }
+    }
+}
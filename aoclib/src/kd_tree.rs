@@ -0,0 +1,232 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::geometry::Point3;
+
+/// Node of a [`KdTree`], splitting its subtree on `axis` at its own point.
+#[derive(Debug, Clone)]
+struct Node {
+    point: usize,
+    axis: usize,
+    left: Option<usize>,
+    right: Option<usize>,
+    /// Bounding box of the subtree.
+    min: Point3<i64>,
+    max: Point3<i64>,
+    /// Largest point index in the subtree.
+    max_index: usize,
+}
+
+/// Static k-d tree over 3D points, answering nearest-neighbour queries and streaming the
+/// closest pairs of points without building every pair.
+#[derive(Debug, Clone)]
+pub struct KdTree {
+    points: Vec<Point3<i64>>,
+    nodes: Vec<Node>,
+    root: Option<usize>,
+}
+
+/// Square of the distance from `p` to the closest point of the box.
+fn min_dist2(p: &Point3<i64>, min: &Point3<i64>, max: &Point3<i64>) -> u128 {
+    (0..3)
+        .map(|axis| {
+            let (v, lo, hi) = (p.get(axis), min.get(axis), max.get(axis));
+            let d = if v < lo {
+                lo.abs_diff(v)
+            } else if v > hi {
+                v.abs_diff(hi)
+            } else {
+                0
+            };
+            u128::from(d) * u128::from(d)
+        })
+        .sum()
+}
+
+/// Square of the distance from `p` to the farthest point of the box.
+fn max_dist2(p: &Point3<i64>, min: &Point3<i64>, max: &Point3<i64>) -> u128 {
+    (0..3)
+        .map(|axis| {
+            let v = p.get(axis);
+            let d = v.abs_diff(min.get(axis)).max(v.abs_diff(max.get(axis)));
+            u128::from(d) * u128::from(d)
+        })
+        .sum()
+}
+
+impl KdTree {
+    pub fn new(points: &[Point3<i64>]) -> Self {
+        let mut tree = Self {
+            points: points.to_vec(),
+            nodes: Vec::with_capacity(points.len()),
+            root: None,
+        };
+        let mut indices = (0..points.len()).collect::<Vec<_>>();
+        tree.root = tree.build(&mut indices, 0);
+        tree
+    }
+
+    fn build(&mut self, indices: &mut [usize], depth: usize) -> Option<usize> {
+        if indices.is_empty() {
+            return None;
+        }
+        let axis = depth % 3;
+        let mid = indices.len() / 2;
+        let points = &self.points;
+        indices.select_nth_unstable_by_key(mid, |&i| (points[i].get(axis), i));
+        let point = indices[mid];
+        let (left_indices, rest) = indices.split_at_mut(mid);
+        let left = self.build(left_indices, depth + 1);
+        let right = self.build(&mut rest[1..], depth + 1);
+
+        let p = self.points[point];
+        let (mut min, mut max, mut max_index) = (p, p, point);
+        for child in [left, right].into_iter().flatten() {
+            let child = &self.nodes[child];
+            min = Point3::new(
+                min.x.min(child.min.x),
+                min.y.min(child.min.y),
+                min.z.min(child.min.z),
+            );
+            max = Point3::new(
+                max.x.max(child.max.x),
+                max.y.max(child.max.y),
+                max.z.max(child.max.z),
+            );
+            max_index = max_index.max(child.max_index);
+        }
+        self.nodes.push(Node {
+            point,
+            axis,
+            left,
+            right,
+            min,
+            max,
+            max_index,
+        });
+        Some(self.nodes.len() - 1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn points(&self) -> &[Point3<i64>] {
+        &self.points
+    }
+
+    /// Closest point to `target` with an index above `after` (all of them when `None`) that
+    /// comes strictly after `last` in `(squared distance, index)` order, as
+    /// `(squared distance, index)`.
+    fn nearest_where(
+        &self,
+        target: &Point3<i64>,
+        after: Option<usize>,
+        last: Option<(u128, usize)>,
+    ) -> Option<(u128, usize)> {
+        let mut best: Option<(u128, usize)> = None;
+        let mut stack = Vec::from_iter(self.root);
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            if after.is_some_and(|after| node.max_index <= after)
+                || best.is_some_and(|(d, _)| min_dist2(target, &node.min, &node.max) > d)
+                || last.is_some_and(|(d, _)| max_dist2(target, &node.min, &node.max) < d)
+            {
+                continue;
+            }
+            let key = (target.dist2(&self.points[node.point]), node.point);
+            if after.is_none_or(|after| node.point > after)
+                && last.is_none_or(|last| key > last)
+                && best.is_none_or(|best| key < best)
+            {
+                best = Some(key);
+            }
+            // visit the side of the target first so that `best` shrinks early
+            let (near, far) = if target.get(node.axis) < self.points[node.point].get(node.axis) {
+                (node.left, node.right)
+            } else {
+                (node.right, node.left)
+            };
+            stack.extend(far);
+            stack.extend(near);
+        }
+        best
+    }
+
+    /// Index of the point closest to `target`, the smallest one on ties.
+    pub fn nearest(&self, target: &Point3<i64>) -> Option<usize> {
+        self.nearest_where(target, None, None).map(|(_, i)| i)
+    }
+
+    /// Every pair of points `(a, b)`, `a < b`, with its squared distance, from the closest to
+    /// the farthest, ties ordered by `(a, b)`. Pairs are computed as they are consumed, so
+    /// taking the first K costs about K nearest-neighbour queries.
+    pub fn closest_pairs(&self) -> ClosestPairs<'_> {
+        let heap = (0..self.len())
+            .filter_map(|a| {
+                let (d, b) = self.nearest_where(&self.points[a], Some(a), None)?;
+                Some(Reverse((d, a, b)))
+            })
+            .collect();
+        ClosestPairs { tree: self, heap }
+    }
+}
+
+/// Iterator returned by [`KdTree::closest_pairs`].
+#[derive(Debug, Clone)]
+pub struct ClosestPairs<'a> {
+    tree: &'a KdTree,
+    /// Next closest pair of each point with the points of higher index.
+    heap: BinaryHeap<Reverse<(u128, usize, usize)>>,
+}
+
+impl Iterator for ClosestPairs<'_> {
+    type Item = ((usize, usize), u128);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((d, a, b)) = self.heap.pop()?;
+        let tree = self.tree;
+        if let Some((next_d, next_b)) = tree.nearest_where(&tree.points[a], Some(a), Some((d, b))) {
+            self.heap.push(Reverse((next_d, a, next_b)));
+        }
+        Some(((a, b), d))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::KdTree;
+    use crate::geometry::Point3;
+
+    fn points() -> impl Strategy<Value = Vec<Point3<i64>>> {
+        prop::collection::vec((-20..20i64, -20..20i64, -20..20i64), 0..40)
+            .prop_map(|points| points.into_iter().map(Point3::from).collect())
+    }
+
+    proptest! {
+        #[test]
+        fn prop_closest_pairs(points in points()) {
+            let tree = KdTree::new(&points);
+            let mut expected = (0..points.len())
+                .flat_map(|a| (a + 1..points.len()).map(move |b| (a, b)))
+                .map(|(a, b)| ((a, b), points[a].dist2(&points[b])))
+                .collect::<Vec<_>>();
+            expected.sort_by_key(|&((a, b), d)| (d, a, b));
+            prop_assert_eq!(expected, tree.closest_pairs().collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn prop_nearest(points in points(), target in (-30..30i64, -30..30i64, -30..30i64)) {
+            let target = Point3::from(target);
+            let tree = KdTree::new(&points);
+            let expected = (0..points.len()).min_by_key(|&i| (points[i].dist2(&target), i));
+            prop_assert_eq!(expected, tree.nearest(&target));
+        }
+    }
+}
@@ -0,0 +1,25 @@
+pub mod answers;
+pub mod cards;
+pub mod crypt;
+pub mod cycle;
+pub mod disjoint_set;
+pub mod distinct_window;
+pub mod geometry;
+pub mod gf2;
+pub mod graph;
+pub mod ilp;
+pub mod input;
+pub mod integrity;
+pub mod interpreter;
+pub mod interval;
+pub mod kd_tree;
+pub mod mem;
+pub mod number_theory;
+pub mod offset_map;
+pub mod partial_order;
+pub mod polyomino;
+pub mod runner;
+pub mod sequence;
+pub mod subsequence;
+
+pub use runner::{cli_flag, cli_option, load_input, run_day, run_day_streaming};
@@ -0,0 +1,59 @@
+use std::fs;
+
+/// Resets the peak resident set size of the process, so that [`peak_rss`] measures from now.
+/// Only supported on Linux, does nothing elsewhere.
+pub fn reset_peak() {
+    let _ = fs::write("/proc/self/clear_refs", "5");
+}
+
+/// Peak resident set size of the process in bytes, since it started or since the last
+/// [`reset_peak`]. `None` where the platform doesn't expose it.
+pub fn peak_rss() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let kib = status
+        .lines()
+        .find_map(|l| l.strip_prefix("VmHWM:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kib * 1024)
+}
+
+/// Formats a size in bytes with a binary unit, e.g. `12.3 MiB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.;
+    let mut unit = 0;
+    while size >= 1024. && unit < UNITS.len() - 1 {
+        size /= 1024.;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_size, peak_rss, reset_peak};
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!("512 B", format_size(512));
+        assert_eq!("1.5 KiB", format_size(1536));
+        assert_eq!("12.3 MiB", format_size(12_897_485));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_peak_rss() {
+        reset_peak();
+        let before = peak_rss().unwrap();
+        let v = std::hint::black_box(vec![1u8; 64 << 20]);
+        assert!(peak_rss().unwrap() >= before + (32 << 20));
+        drop(v);
+    }
+}
@@ -0,0 +1,422 @@
+use std::ops::RangeInclusive;
+
+use anyhow::{Context, Result};
+use num_bigint::BigUint;
+
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, `None` when it overflows.
+pub fn lcm(a: u64, b: u64) -> Option<u64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// `(g, x, y)` such that `a * x + b * y = g = gcd(a, b)`, with `g` non-negative.
+pub fn ext_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (1, 0);
+    let (mut y0, mut y1) = (0, 1);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+    if r0 < 0 {
+        (-r0, -x0, -y0)
+    } else {
+        (r0, x0, y0)
+    }
+}
+
+/// `x` in `0..m` such that `a * x ≡ 1 (mod m)`, `None` when `a` and `m` aren't coprime.
+pub fn mod_inverse(a: u64, m: u64) -> Option<u64> {
+    let (g, x, _) = ext_gcd(a as i128, m as i128);
+    (g == 1).then(|| x.rem_euclid(m as i128) as u64)
+}
+
+/// Residue `r` modulo `lcm(m1, m2)` of the integers congruent to `r1` modulo `m1` and to
+/// `r2` modulo `m2`, as `(r, lcm)`, `None` when there is none. Fails when the lcm overflows.
+fn crt_pair((r1, m1): (u64, u64), (r2, m2): (u64, u64)) -> Result<Option<(u64, u64)>> {
+    let (r1, r2) = (r1 % m1, r2 % m2);
+    let (g, x, _) = ext_gcd(m1 as i128, m2 as i128);
+    let diff = r2 as i128 - r1 as i128;
+    if diff % g != 0 {
+        return Ok(None);
+    }
+    let step = (m2 as i128 / g) as u128;
+    let lcm = u64::try_from(m1 as u128 / g as u128 * m2 as u128)
+        .with_context(|| format!("lcm of {m1} and {m2} overflows u64"))?;
+    // r1 + m1 * k with k = diff / g * x modulo m2 / g
+    let (d, x) = (
+        (diff / g).rem_euclid(step as i128) as u128,
+        x.rem_euclid(step as i128) as u128,
+    );
+    let k = d.mulmod(x, step);
+    Ok(Some(((r1 as u128 + m1 as u128 * k) as u64 % lcm, lcm)))
+}
+
+/// Solves the system of congruences `x ≡ r (mod m)` for each `(r, m)`, with moduli that
+/// don't need to be coprime. Returns `(x, lcm of the moduli)` with `x` the smallest
+/// non-negative solution, `None` when the congruences are inconsistent. Fails when the lcm
+/// overflows.
+///
+/// # Panics
+///
+/// Panics when a modulus is zero.
+pub fn crt(congruences: &[(u64, u64)]) -> Result<Option<(u64, u64)>> {
+    assert!(congruences.iter().all(|&(_, m)| m > 0), "zero modulus");
+    let mut solution = (0, 1);
+    for &c in congruences {
+        match crt_pair(solution, c)? {
+            Some(s) => solution = s,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(solution))
+}
+
+/// Modular arithmetic that doesn't overflow on intermediate products.
+pub trait ModArith: Sized {
+    /// `self * other mod m`.
+    fn mulmod(self, other: Self, m: Self) -> Self;
+    /// `self^exp mod m`, by squaring.
+    fn powmod(self, exp: Self, m: Self) -> Self;
+}
+
+impl ModArith for u64 {
+    fn mulmod(self, other: u64, m: u64) -> u64 {
+        (self as u128 * other as u128 % m as u128) as u64
+    }
+
+    fn powmod(self, mut exp: u64, m: u64) -> u64 {
+        let (mut base, mut result) = (self % m, 1 % m);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mulmod(base, m);
+            }
+            base = base.mulmod(base, m);
+            exp >>= 1;
+        }
+        result
+    }
+}
+
+/// `a + b mod m` for `a` and `b` below `m`.
+fn add_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+impl ModArith for u128 {
+    /// Double and add, as the product may not fit in any primitive integer.
+    fn mulmod(self, other: u128, m: u128) -> u128 {
+        let (mut a, mut b) = (self % m, other % m);
+        let mut result = 0;
+        while b > 0 {
+            if b & 1 == 1 {
+                result = add_mod_u128(result, a, m);
+            }
+            a = add_mod_u128(a, a, m);
+            b >>= 1;
+        }
+        result
+    }
+
+    fn powmod(self, mut exp: u128, m: u128) -> u128 {
+        let (mut base, mut result) = (self % m, 1 % m);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result.mulmod(base, m);
+            }
+            base = base.mulmod(base, m);
+            exp >>= 1;
+        }
+        result
+    }
+}
+
+/// Largest `r` such that `r * r <= n`, by Newton's method.
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // a power of two above the root, from which the iterates decrease to it
+    let mut x = 1 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
+}
+
+/// Integers `t` such that `t * (total - t) > threshold`, e.g. the times to hold the button
+/// to beat a record in a boat race, `None` when there are none. They form a window centred on
+/// `total / 2`.
+///
+/// The bounds are the roots of `t^2 - total * t + threshold`, from the integer square root of
+/// the discriminant, exact in `u128` when `total` fits in a `u64` and computed with big
+/// integers otherwise, then corrected by checking the products next to them.
+pub fn product_window(total: u128, threshold: u128) -> Option<RangeInclusive<u128>> {
+    let beats = |t: u128| t.checked_mul(total - t).is_none_or(|p| p > threshold);
+    if !beats(total / 2) {
+        return None;
+    }
+    // the discriminant is positive, and 4 * threshold < total^2 doesn't overflow
+    let root = match u64::try_from(total) {
+        Ok(_) => isqrt(total * total - 4 * threshold),
+        Err(_) => {
+            let disc = BigUint::from(total).pow(2) - BigUint::from(threshold) * 4u8;
+            u128::try_from(disc.sqrt()).ok()?
+        }
+    };
+    let mut low = (total - root) / 2;
+    while low > 0 && beats(low - 1) {
+        low -= 1;
+    }
+    while !beats(low) {
+        low += 1;
+    }
+    Some(low..=total - low)
+}
+
+/// Divisors of `n`, in increasing order, by trial division up to its square root.
+pub fn divisors(n: u64) -> Vec<u64> {
+    let (mut small, mut large) = (Vec::new(), Vec::new());
+    for d in (1..=isqrt(n as u128) as u64).filter(|&d| n.is_multiple_of(d)) {
+        small.push(d);
+        if d != n / d {
+            large.push(n / d);
+        }
+    }
+    small.extend(large.into_iter().rev());
+    small
+}
+
+/// Möbius function: `0` when `n` has a square factor, else `-1` to the number of its prime
+/// factors.
+///
+/// Trial division, stopping as soon as what is left of `n` is prime rather than going up to
+/// its square root.
+///
+/// # Panics
+///
+/// Panics when `n` is zero.
+pub fn mobius(mut n: u64) -> i64 {
+    assert!(n > 0, "mobius(0) is undefined");
+    let mut result = 1;
+    let mut p = 2;
+    let mut left_is_prime = is_prime(n);
+    while !left_is_prime && p <= n / p {
+        if n.is_multiple_of(p) {
+            n /= p;
+            if n.is_multiple_of(p) {
+                return 0;
+            }
+            result = -result;
+            left_is_prime = is_prime(n);
+        }
+        p += 1;
+    }
+    if n > 1 {
+        result = -result;
+    }
+    result
+}
+
+/// Deterministic Miller-Rabin test, the bases used being enough for every `u64`.
+pub fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    if let Some(&p) = BASES.iter().find(|&&p| n.is_multiple_of(p)) {
+        return n == p;
+    }
+    let shift = (n - 1).trailing_zeros();
+    let odd = (n - 1) >> shift;
+    BASES.iter().all(|&base| {
+        let mut x = base.powmod(odd, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        (1..shift).any(|_| {
+            x = x.mulmod(x, n);
+            x == n - 1
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use proptest::prelude::*;
+
+    use super::{
+        crt, divisors, ext_gcd, gcd, is_prime, isqrt, lcm, mobius, mod_inverse, product_window,
+        ModArith,
+    };
+
+    /// Window of [`product_window`] with the floating point roots, as 2023 d06 used to.
+    fn float_window(total: u64, threshold: u64) -> (u64, u64) {
+        let (total, threshold) = (total as f64, threshold as f64);
+        let delta_sqrt = (total * total - 4. * threshold).sqrt();
+        let low = ((total - delta_sqrt) / 2.).floor() as u64 + 1;
+        let high = ((total + delta_sqrt) / 2.).ceil() as u64 - 1;
+        (low, high)
+    }
+
+    #[test]
+    fn test_number_theory() {
+        assert_eq!(6, gcd(54, 24));
+        assert_eq!(Some(216), lcm(54, 24));
+        assert_eq!(None, lcm(u64::MAX, u64::MAX - 1));
+        assert_eq!((6, 1, -2), ext_gcd(54, 24));
+        assert_eq!(Some(4), mod_inverse(3, 11));
+        assert_eq!(None, mod_inverse(4, 10));
+        assert_eq!(Some((23, 105)), crt(&[(2, 3), (3, 5), (2, 7)]).unwrap());
+        assert_eq!(Some((10, 12)), crt(&[(4, 6), (2, 4)]).unwrap());
+        assert_eq!(None, crt(&[(1, 6), (2, 4)]).unwrap());
+        assert_eq!(Some((0, 1)), crt(&[]).unwrap());
+        // inconsistent before the lcm gets too large, then consistent with an lcm of 2^64
+        assert_eq!(None, crt(&[(0, 2), (1, 2), (0, 1 << 63), (0, 3)]).unwrap());
+        assert!(crt(&[(0, 1 << 63), (0, 3)]).is_err());
+        assert_eq!(vec![1, 2, 3, 4, 6, 12], divisors(12));
+        assert_eq!(vec![1, 7, 49], divisors(49));
+        let mu = (1..=12).map(mobius).collect::<Vec<_>>();
+        assert_eq!(vec![1, -1, -1, 0, -1, 1, -1, 0, 0, 1, -1, 0], mu);
+        assert_eq!(u64::MAX as u128, isqrt(u128::MAX));
+        assert!(is_prime(2) && is_prime((1 << 61) - 1) && is_prime(18_446_744_073_709_551_557));
+        assert!(!is_prime(1) && !is_prime(561) && !is_prime(3_215_031_751));
+        // Fermat's little theorem for the prime 2^127 - 1
+        let p = (1u128 << 127) - 1;
+        assert_eq!(1, 3u128.powmod(p - 1, p));
+        assert_eq!(0, 5u64.powmod(3, 1));
+    }
+
+    #[test]
+    fn test_product_window() {
+        assert_eq!(Some(2..=5), product_window(7, 9));
+        assert_eq!(Some(11..=19), product_window(30, 200));
+        assert_eq!(None, product_window(4, 4));
+        assert_eq!(Some(2..=2), product_window(4, 3));
+        assert_eq!(None, product_window(1, 0));
+        assert_eq!(
+            Some(2..=u128::MAX - 2),
+            product_window(u128::MAX, u128::MAX)
+        );
+        assert_eq!(Some(1..=u128::MAX - 1), product_window(u128::MAX, 0));
+
+        // the threshold is exactly the product at `low`, which doesn't beat it, but the f64
+        // discriminant is rounded and the window found with it starts at `low`
+        let total = 3_000_000_000;
+        let low = 999_999_937;
+        let threshold = low * (total - low);
+        let window = product_window(total as u128, threshold as u128).unwrap();
+        assert_eq!(low as u128 + 1..=(total - low) as u128 - 1, window);
+        assert_eq!(low, float_window(total, threshold).0);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_gcd(a in 1..1000u64, b in 1..1000u64) {
+            let g = gcd(a, b);
+            prop_assert_eq!((1..=a.min(b)).rev().find(|d| a % d == 0 && b % d == 0), Some(g));
+            prop_assert_eq!(Some(a * b / g), lcm(a, b));
+            let (eg, x, y) = ext_gcd(a as i128, -(b as i128));
+            prop_assert_eq!(g as i128, eg);
+            prop_assert_eq!(eg, a as i128 * x - b as i128 * y);
+        }
+
+        #[test]
+        fn prop_mod_inverse(a in any::<u64>(), m in 2..u64::MAX) {
+            match mod_inverse(a, m) {
+                Some(x) => prop_assert_eq!(1, a.mulmod(x, m)),
+                None => prop_assert_ne!(1, gcd(a, m)),
+            }
+        }
+
+        #[test]
+        fn prop_crt(congruences in prop::collection::vec((0..50u64, 1..13u64), 0..4)) {
+            let m = congruences.iter().fold(1, |acc, &(_, m)| lcm(acc, m).unwrap());
+            let brute = (0..m).find(|x| congruences.iter().all(|&(r, mi)| x % mi == r % mi));
+            prop_assert_eq!(brute.map(|x| (x, m)), crt(&congruences).unwrap());
+        }
+
+        #[test]
+        fn prop_crt_large(x in any::<u64>(), m1 in 1..1u64 << 32, m2 in 1..1u64 << 32) {
+            let (r, m) = crt(&[(x, m1), (x, m2)]).unwrap().unwrap();
+            prop_assert_eq!(lcm(m1, m2).unwrap(), m);
+            prop_assert_eq!(x % m, r);
+        }
+
+        #[test]
+        fn prop_modarith(a in any::<u128>(), b in any::<u128>(), e in any::<u64>(), m in 1..u128::MAX) {
+            let big = |n: u128| BigUint::from(n);
+            let to_u128 = |n: BigUint| u128::try_from(n).unwrap();
+            prop_assert_eq!(to_u128(big(a) * big(b) % big(m)), a.mulmod(b, m));
+            prop_assert_eq!(to_u128(big(a).modpow(&big(e as u128), &big(m))), a.powmod(e as u128, m));
+            let (a, b, m) = (a as u64, b as u64, (m as u64).max(1));
+            prop_assert_eq!((a as u128 * b as u128 % m as u128) as u64, a.mulmod(b, m));
+            let expected = BigUint::from(a).modpow(&BigUint::from(e), &BigUint::from(m));
+            prop_assert_eq!(u64::try_from(expected).unwrap(), a.powmod(e, m));
+        }
+
+        #[test]
+        fn prop_isqrt(n in any::<u128>()) {
+            let r = isqrt(n);
+            prop_assert!(r * r <= n);
+            prop_assert!((r + 1).checked_mul(r + 1).is_none_or(|s| s > n));
+        }
+
+        #[test]
+        fn prop_divisors_and_primes(n in 1..5000u64) {
+            let brute = (1..=n).filter(|&d| n.is_multiple_of(d)).collect::<Vec<_>>();
+            prop_assert_eq!(n > 1 && brute.len() == 2, is_prime(n));
+            // the Möbius function sums to zero over the divisors of any n > 1
+            prop_assert_eq!(i64::from(n == 1), brute.iter().map(|&d| mobius(d)).sum::<i64>());
+            prop_assert_eq!(brute, divisors(n));
+        }
+
+        #[test]
+        fn prop_mobius_near_max(f in 1..50u64, k in 0..1000u64) {
+            // f times the largest prime q below u64::MAX / f - k, q being larger than f
+            let q = (1..=u64::MAX / f - k).rev().find(|&q| is_prime(q)).unwrap();
+            prop_assert_eq!(-mobius(f), mobius(f * q));
+        }
+
+        #[test]
+        fn prop_product_window(total in 0..200u128, threshold in 0..12000u128) {
+            let brute = (0..=total).filter(|t| t * (total - t) > threshold).collect::<Vec<_>>();
+            let window = product_window(total, threshold);
+            prop_assert_eq!(brute, window.into_iter().flatten().collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn prop_product_window_large(total in any::<u128>(), threshold in any::<u128>()) {
+            let product = |t: u128| BigUint::from(t) * BigUint::from(total - t);
+            let threshold_big = BigUint::from(threshold);
+            match product_window(total, threshold) {
+                Some(window) => {
+                    let (low, high) = window.into_inner();
+                    prop_assert_eq!(total - low, high);
+                    prop_assert!(product(low) > threshold_big);
+                    prop_assert!(low == 0 || product(low - 1) <= threshold_big);
+                }
+                None => prop_assert!(product(total / 2) <= threshold_big),
+            }
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::ops::RangeBounds;
+
+use crate::interval::{inclusive_bounds, Int, IntervalSet};
+
+/// Function on integers that adds a constant offset on each of a set of disjoint ranges and is
+/// the identity everywhere else, such as the "destination source length" tables of almanacs.
+/// Maps compose into a single map with [`OffsetMap::then`], and whole sets of ranges can be
+/// pushed through one with [`OffsetMap::image`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OffsetMap<T> {
+    /// Sorted, disjoint `(start, end, offset)` pieces with a non-zero offset; adjacent pieces
+    /// have different offsets.
+    pieces: Vec<(T, T, i128)>,
+}
+
+impl<T> Default for OffsetMap<T> {
+    fn default() -> Self {
+        Self { pieces: Vec::new() }
+    }
+}
+
+impl<T: Int> OffsetMap<T> {
+    /// The identity.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a map from sorted pieces covering any part of the domain, dropping identity
+    /// pieces and merging adjacent ones with the same offset.
+    fn from_sorted(sorted: impl IntoIterator<Item = (T, T, i128)>) -> Self {
+        let mut pieces: Vec<(T, T, i128)> = Vec::new();
+        for (lo, hi, offset) in sorted {
+            if offset == 0 {
+                continue;
+            }
+            match pieces.last_mut() {
+                Some(last) if last.2 == offset && last.1.succ() == Some(lo) => last.1 = hi,
+                _ => pieces.push((lo, hi, offset)),
+            }
+        }
+        Self { pieces }
+    }
+
+    /// Maps every value of `range` to itself plus `offset`, replacing what the map did there.
+    pub fn insert(&mut self, range: impl RangeBounds<T>, offset: i128) {
+        let Some((lo, hi)) = inclusive_bounds(range) else {
+            return;
+        };
+        let pieces = self
+            .segments(T::MIN, T::MAX)
+            .into_iter()
+            .flat_map(|(start, end, old)| {
+                if end < lo || hi < start {
+                    return vec![(start, end, old)];
+                }
+                let mut split = Vec::with_capacity(3);
+                if start < lo {
+                    split.push((start, lo.pred().unwrap(), old));
+                }
+                split.push((start.max(lo), end.min(hi), offset));
+                if hi < end {
+                    split.push((hi.succ().unwrap(), end, old));
+                }
+                split
+            });
+        *self = Self::from_sorted(pieces);
+    }
+
+    /// Pieces covering exactly `lo..=hi`, with an offset of 0 where the map is the identity.
+    fn segments(&self, lo: T, hi: T) -> Vec<(T, T, i128)> {
+        let mut segments = Vec::new();
+        let mut next = Some(lo);
+        let first = self.pieces.partition_point(|p| p.1 < lo);
+        for &(start, end, offset) in &self.pieces[first..] {
+            let Some(from) = next.filter(|&from| from <= hi) else {
+                break;
+            };
+            if hi < start {
+                break;
+            }
+            if from < start {
+                segments.push((from, start.pred().unwrap(), 0));
+            }
+            segments.push((from.max(start), end.min(hi), offset));
+            next = end.succ();
+        }
+        if let Some(from) = next.filter(|&from| from <= hi) {
+            segments.push((from, hi, 0));
+        }
+        segments
+    }
+
+    fn shift(value: T, offset: i128) -> T {
+        T::from_i128(value.to_i128() + offset).expect("offset map result out of range")
+    }
+
+    /// Image of `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the image doesn't fit in `T`.
+    pub fn apply(&self, value: T) -> T {
+        let i = self.pieces.partition_point(|p| p.1 < value);
+        match self.pieces.get(i) {
+            Some(&(start, _, offset)) if start <= value => Self::shift(value, offset),
+            _ => value,
+        }
+    }
+
+    /// Map applying `self`, then `next`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when an intermediate image doesn't fit in `T`.
+    pub fn then(&self, next: &Self) -> Self {
+        let mut pieces = Vec::new();
+        for (lo, hi, offset) in self.segments(T::MIN, T::MAX) {
+            let (image_lo, image_hi) = (Self::shift(lo, offset), Self::shift(hi, offset));
+            for (start, end, next_offset) in next.segments(image_lo, image_hi) {
+                pieces.push((
+                    Self::shift(start, -offset),
+                    Self::shift(end, -offset),
+                    offset + next_offset,
+                ));
+            }
+        }
+        Self::from_sorted(pieces)
+    }
+
+    /// Image of every value of `set`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when an image doesn't fit in `T`.
+    pub fn image(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        set.iter()
+            .flat_map(|range| self.segments(*range.start(), *range.end()))
+            .map(|(lo, hi, offset)| Self::shift(lo, offset)..=Self::shift(hi, offset))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::OffsetMap;
+    use crate::interval::IntervalSet;
+
+    fn almanac_map(table: &[(u64, u64, u64)]) -> OffsetMap<u64> {
+        let mut map = OffsetMap::new();
+        for &(dst, src, len) in table {
+            map.insert(src..src + len, dst as i128 - src as i128);
+        }
+        map
+    }
+
+    #[test]
+    fn test_compose() {
+        let seed_to_soil = almanac_map(&[(50, 98, 2), (52, 50, 48)]);
+        let soil_to_fertilizer = almanac_map(&[(0, 15, 37), (37, 52, 2), (39, 0, 15)]);
+        let map = seed_to_soil.then(&soil_to_fertilizer);
+        for (seed, fertilizer) in [(79, 81), (14, 53), (55, 57), (13, 52), (98, 35)] {
+            assert_eq!(fertilizer, map.apply(seed));
+        }
+
+        let seeds: IntervalSet<u64> = [79..93, 55..68].into_iter().collect();
+        let soil = seed_to_soil.image(&seeds);
+        assert_eq!(vec![57..=69, 81..=94], soil.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_insert_overrides() {
+        let mut map = OffsetMap::<i32>::new();
+        map.insert(0..10, 5);
+        map.insert(3..=4, -2);
+        map.insert(8.., 5);
+        assert_eq!(
+            vec![5, 6, 7, 1, 2, 10, 11, 12, 13, 14, 15],
+            (0..=10).map(|v| map.apply(v)).collect::<Vec<_>>()
+        );
+        map.insert(.., 0);
+        assert_eq!(OffsetMap::new(), map);
+    }
+
+    fn map() -> impl Strategy<Value = OffsetMap<i32>> {
+        prop::collection::vec((-100..100i32, 0..40i32, -50..50i128), 0..6).prop_map(|pieces| {
+            let mut map = OffsetMap::new();
+            for (start, len, offset) in pieces {
+                map.insert(start..start + len, offset);
+            }
+            map
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_then(a in map(), b in map(), c in map()) {
+            let composed = a.then(&b).then(&c);
+            prop_assert_eq!(&composed, &a.then(&b.then(&c)));
+            for v in -400..400 {
+                prop_assert_eq!(c.apply(b.apply(a.apply(v))), composed.apply(v));
+            }
+        }
+
+        #[test]
+        fn prop_image(map in map(), ranges in prop::collection::vec((-200..200i32, 0..50i32), 0..5)) {
+            let set: IntervalSet<i32> = ranges.iter().map(|&(start, len)| start..start + len).collect();
+            let mut expected = IntervalSet::new();
+            for v in -400..400 {
+                if set.contains(v) {
+                    expected.insert(map.apply(v)..=map.apply(v));
+                }
+            }
+            prop_assert_eq!(expected, map.image(&set));
+        }
+    }
+}
@@ -0,0 +1,329 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fmt::{self, Debug, Display};
+use std::hash::Hash;
+
+/// Rules requiring some items to come before others when both are present, e.g. the page
+/// ordering rules of 2024 d05. They needn't be consistent, transitive nor total: only the
+/// rules between the items of a sequence apply to it.
+#[derive(Debug, Clone)]
+pub struct Precedence<T> {
+    /// Items that must come after each item.
+    after: HashMap<T, HashSet<T>>,
+}
+
+/// Rule broken by a sequence: `after` is at `after_pos`, before `before` at `before_pos`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Violation<T> {
+    pub before: T,
+    pub after: T,
+    pub before_pos: usize,
+    pub after_pos: usize,
+}
+
+impl<T: Debug> Display for Violation<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} at {} comes before {:?} at {}, against the rule {:?} before {:?}",
+            self.after, self.after_pos, self.before, self.before_pos, self.before, self.after
+        )
+    }
+}
+
+/// Order of some items following the rules between them, by [`Precedence::linearize`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Linearization<T> {
+    pub order: Vec<T>,
+    /// Whether it is the only order following the rules.
+    pub unique: bool,
+}
+
+impl<T> Default for Precedence<T> {
+    fn default() -> Self {
+        Self {
+            after: HashMap::new(),
+        }
+    }
+}
+
+/// Outcome of Kahn's algorithm on the rules between some items.
+enum Kahn {
+    /// Positions in topological order, and whether it is the only one.
+    Sorted(Vec<usize>, bool),
+    /// Position of each item left, those on or after a cycle.
+    Cyclic(Vec<usize>),
+}
+
+impl<T: Copy + Eq + Hash> Precedence<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requires `before` to come before `after`.
+    pub fn add_rule(&mut self, before: T, after: T) {
+        self.after.entry(before).or_default().insert(after);
+    }
+
+    /// Successors of each position of `items` under the rules between them, in increasing
+    /// order.
+    fn restrict(&self, items: &[T]) -> Vec<Vec<usize>> {
+        let positions = items
+            .iter()
+            .enumerate()
+            .map(|(pos, &item)| (item, pos))
+            .collect::<HashMap<_, _>>();
+        items
+            .iter()
+            .map(|item| {
+                let mut successors = self
+                    .after
+                    .get(item)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|after| positions.get(after).copied())
+                    .collect::<Vec<_>>();
+                successors.sort_unstable();
+                successors
+            })
+            .collect()
+    }
+
+    /// Kahn's algorithm, taking the earliest available position first.
+    fn kahn(&self, items: &[T]) -> Kahn {
+        let successors = self.restrict(items);
+        let mut in_degree = vec![0; items.len()];
+        for &s in successors.iter().flatten() {
+            in_degree[s] += 1;
+        }
+        let mut available = (0..items.len())
+            .filter(|&pos| in_degree[pos] == 0)
+            .map(Reverse)
+            .collect::<BinaryHeap<_>>();
+        let mut order = Vec::with_capacity(items.len());
+        let mut unique = true;
+        while let Some(Reverse(pos)) = available.pop() {
+            unique &= available.is_empty();
+            order.push(pos);
+            for &s in &successors[pos] {
+                in_degree[s] -= 1;
+                if in_degree[s] == 0 {
+                    available.push(Reverse(s));
+                }
+            }
+        }
+        match order.len() == items.len() {
+            true => Kahn::Sorted(order, unique),
+            false => Kahn::Cyclic((0..items.len()).filter(|&p| in_degree[p] > 0).collect()),
+        }
+    }
+
+    /// First broken rule in `items`, distinct, by position of its later item then of its
+    /// earlier one, `None` when they follow every rule.
+    pub fn violation(&self, items: &[T]) -> Option<Violation<T>> {
+        self.restrict(items)
+            .iter()
+            .enumerate()
+            .find_map(|(before_pos, successors)| {
+                // the successors are sorted, so the first one is the earliest
+                let after_pos = *successors.first().filter(|&&s| s < before_pos)?;
+                Some(Violation {
+                    before: items[before_pos],
+                    after: items[after_pos],
+                    before_pos,
+                    after_pos,
+                })
+            })
+    }
+
+    /// Order of `items`, distinct, following the rules between them, `None` when they form a
+    /// cycle. When several items could come next, the earliest in `items` does, so that items
+    /// already in order stay as they are.
+    pub fn linearize(&self, items: &[T]) -> Option<Linearization<T>> {
+        match self.kahn(items) {
+            Kahn::Sorted(order, unique) => Some(Linearization {
+                order: order.into_iter().map(|p| items[p]).collect(),
+                unique,
+            }),
+            Kahn::Cyclic(_) => None,
+        }
+    }
+
+    /// Whether the item at `pos` in `items`, distinct and following consistent rules, is at
+    /// the same position in every order following them: it has to come before or after each
+    /// other item, directly or through others.
+    pub fn is_fixed(&self, items: &[T], pos: usize) -> bool {
+        let successors = self.restrict(items);
+        let mut predecessors = vec![Vec::new(); items.len()];
+        for (p, next) in successors.iter().enumerate() {
+            for &s in next {
+                predecessors[s].push(p);
+            }
+        }
+        let reachable = |edges: &[Vec<usize>]| {
+            let mut seen = vec![false; items.len()];
+            let mut stack = vec![pos];
+            seen[pos] = true;
+            while let Some(p) = stack.pop() {
+                for &next in &edges[p] {
+                    if !seen[next] {
+                        seen[next] = true;
+                        stack.push(next);
+                    }
+                }
+            }
+            seen.into_iter().filter(|&s| s).count() - 1
+        };
+        reachable(&successors) + reachable(&predecessors) == items.len() - 1
+    }
+
+    /// Cycle of rules between `items`, distinct, each item of it having to come before the
+    /// next and the last one before the first, `None` when they are consistent.
+    pub fn cycle(&self, items: &[T]) -> Option<Vec<T>> {
+        let Kahn::Cyclic(left) = self.kahn(items) else {
+            return None;
+        };
+        // every item left has a predecessor left, so walking back from any of them loops
+        let is_left = left.iter().copied().collect::<HashSet<_>>();
+        let mut predecessor = HashMap::new();
+        let successors = self.restrict(items);
+        for &pos in &left {
+            for &s in successors[pos].iter().filter(|s| is_left.contains(s)) {
+                predecessor.entry(s).or_insert(pos);
+            }
+        }
+        let mut seen = HashMap::new();
+        let mut walk = Vec::new();
+        let mut pos = *left.first()?;
+        while !seen.contains_key(&pos) {
+            seen.insert(pos, walk.len());
+            walk.push(pos);
+            pos = predecessor[&pos];
+        }
+        let mut cycle = walk.split_off(seen[&pos]);
+        cycle.reverse();
+        Some(cycle.into_iter().map(|p| items[p]).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{Linearization, Precedence, Violation};
+
+    fn permutations(items: &[u8]) -> Vec<Vec<u8>> {
+        if items.is_empty() {
+            return vec![Vec::new()];
+        }
+        (0..items.len())
+            .flat_map(|i| {
+                let mut rest = items.to_vec();
+                let first = rest.remove(i);
+                permutations(&rest).into_iter().map(move |mut p| {
+                    p.insert(0, first);
+                    p
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_precedence() {
+        let mut rules = Precedence::new();
+        for (before, after) in [(1, 2), (2, 3), (1, 3), (4, 3)] {
+            rules.add_rule(before, after);
+        }
+        assert_eq!(None, rules.violation(&[1, 2, 3]));
+        let violation = rules.violation(&[3, 1, 2]).unwrap();
+        let expected = Violation {
+            before: 1,
+            after: 3,
+            before_pos: 1,
+            after_pos: 0,
+        };
+        assert_eq!(expected, violation);
+        assert_eq!(
+            "3 at 0 comes before 1 at 1, against the rule 1 before 3",
+            violation.to_string()
+        );
+
+        let sorted = |order: Vec<i32>, unique| Some(Linearization { order, unique });
+        assert_eq!(sorted(vec![1, 2, 3], true), rules.linearize(&[3, 2, 1]));
+        // 4 only has to come before 3, so it stays first
+        assert_eq!(
+            sorted(vec![4, 1, 2, 3], false),
+            rules.linearize(&[4, 3, 2, 1])
+        );
+        assert_eq!(sorted(vec![5, 6], false), rules.linearize(&[5, 6]));
+        assert_eq!(None, rules.cycle(&[1, 2, 3, 4]));
+        // 1 and 2 come before 3 and 4 isn't ordered with them, but 3 comes last
+        assert!(!rules.is_fixed(&[4, 1, 2, 3], 0));
+        assert!(!rules.is_fixed(&[4, 1, 2, 3], 1));
+        assert!(rules.is_fixed(&[4, 1, 2, 3], 3));
+        assert!(rules.is_fixed(&[3, 2, 1], 1));
+
+        rules.add_rule(3, 1);
+        assert_eq!(None, rules.linearize(&[1, 2, 3]));
+        assert_eq!(Some(vec![1, 2, 3]), rules.cycle(&[4, 3, 2, 1]));
+        // without 2, the cycle is the two rules between 1 and 3
+        assert_eq!(Some(vec![3, 1]), rules.cycle(&[1, 3]));
+        assert!(rules.linearize(&[1, 2]).is_some());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_linearize(
+            rule_list in prop::collection::vec((0..8u8, 0..8u8), 0..20),
+            items in prop::sample::subsequence((0..8u8).collect::<Vec<_>>(), 0..7)
+                .prop_shuffle(),
+        ) {
+            // no order follows a rule between an item and itself
+            let rule_list = rule_list.into_iter().filter(|(a, b)| a != b).collect::<Vec<_>>();
+            let mut rules = Precedence::new();
+            for &(a, b) in &rule_list {
+                rules.add_rule(a, b);
+            }
+            let follows = |order: &[u8]| {
+                let pos = |x| order.iter().position(|&y| y == x);
+                rule_list.iter().all(|&(a, b)| match (pos(a), pos(b)) {
+                    (Some(i), Some(j)) => i < j,
+                    _ => true,
+                })
+            };
+            prop_assert_eq!(follows(&items), rules.violation(&items).is_none());
+            match (rules.linearize(&items), rules.cycle(&items)) {
+                (Some(linearization), None) => {
+                    let mut sorted = linearization.order.clone();
+                    sorted.sort_unstable();
+                    let mut expected = items.clone();
+                    expected.sort_unstable();
+                    prop_assert_eq!(expected, sorted);
+                    prop_assert!(follows(&linearization.order));
+                    if follows(&items) {
+                        prop_assert_eq!(&items, &linearization.order);
+                    }
+                    // every order following the rules, as permutations of the items
+                    let orders = permutations(&items)
+                        .into_iter()
+                        .filter(|o| follows(o))
+                        .collect::<Vec<_>>();
+                    prop_assert_eq!(orders.len() == 1, linearization.unique);
+                    for (pos, &item) in items.iter().enumerate() {
+                        let at = |o: &Vec<u8>| o.iter().position(|&x| x == item);
+                        let fixed = orders.iter().all(|o| at(o) == at(&orders[0]));
+                        prop_assert_eq!(fixed, rules.is_fixed(&items, pos));
+                    }
+                }
+                (None, Some(cycle)) => {
+                    prop_assert!(!cycle.is_empty());
+                    for (i, &a) in cycle.iter().enumerate() {
+                        let b = cycle[(i + 1) % cycle.len()];
+                        prop_assert!(rule_list.contains(&(a, b)));
+                    }
+                }
+                _ => prop_assert!(false, "linearize and cycle disagree"),
+            }
+        }
+    }
+}
@@ -0,0 +1,395 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{bail, Error, Result};
+
+/// Set of cells of a grid, normalized so that its topmost row and leftmost column are `0`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Polyomino {
+    /// `(row, col)` of each cell, in row-major order.
+    cells: Vec<(usize, usize)>,
+    width: usize,
+    height: usize,
+}
+
+impl Polyomino {
+    /// Polyomino made of `cells`, `None` when there are none.
+    pub fn new(cells: impl IntoIterator<Item = (usize, usize)>) -> Option<Self> {
+        let mut cells = cells.into_iter().collect::<Vec<_>>();
+        let top = cells.iter().map(|&(r, _)| r).min()?;
+        let left = cells.iter().map(|&(_, c)| c).min()?;
+        cells
+            .iter_mut()
+            .for_each(|(r, c)| (*r, *c) = (*r - top, *c - left));
+        cells.sort_unstable();
+        cells.dedup();
+        Some(Self {
+            width: cells.iter().map(|&(_, c)| c + 1).max()?,
+            height: cells.iter().map(|&(r, _)| r + 1).max()?,
+            cells,
+        })
+    }
+
+    pub fn cells(&self) -> &[(usize, usize)] {
+        &self.cells
+    }
+
+    /// Number of cells.
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Quarter turn clockwise.
+    pub fn rotate(&self) -> Self {
+        Self::new(self.cells.iter().map(|&(r, c)| (c, self.height - 1 - r)))
+            .expect("a polyomino is never empty")
+    }
+
+    /// Mirror image through the vertical axis.
+    pub fn reflect(&self) -> Self {
+        Self::new(self.cells.iter().map(|&(r, c)| (r, self.width - 1 - c)))
+            .expect("a polyomino is never empty")
+    }
+
+    /// Distinct rotations and reflections, between 1 and 8 of them, starting with `self`.
+    pub fn orientations(&self) -> Vec<Self> {
+        let mut orientations = Vec::with_capacity(8);
+        for mut p in [self.clone(), self.reflect()] {
+            for _ in 0..4 {
+                let next = p.rotate();
+                if !orientations.contains(&p) {
+                    orientations.push(p);
+                }
+                p = next;
+            }
+        }
+        orientations
+    }
+}
+
+/// Parses rows of `#` (cell) and `.` (no cell).
+impl FromStr for Polyomino {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut cells = Vec::new();
+        for (r, line) in s.lines().enumerate() {
+            for (c, ch) in line.chars().enumerate() {
+                match ch {
+                    '#' => cells.push((r, c)),
+                    '.' => {}
+                    _ => bail!("unexpected {ch:?} in polyomino"),
+                }
+            }
+        }
+        match Self::new(cells) {
+            Some(p) => Ok(p),
+            None => bail!("polyomino without any cell"),
+        }
+    }
+}
+
+/// Piece laid in a region by [`pack`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placement {
+    /// Index of the piece in the slice given to [`pack`].
+    pub piece: usize,
+    /// `(row, col)` of each covered cell of the region.
+    pub cells: Vec<(usize, usize)>,
+}
+
+/// Pieces laid without overlap in a `width` × `height` region, rendered with one letter per
+/// placement and `.` for uncovered cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub width: usize,
+    pub height: usize,
+    pub placements: Vec<Placement>,
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+        let mut grid = vec![vec!['.'; self.width]; self.height];
+        for (i, placement) in self.placements.iter().enumerate() {
+            let letter = LETTERS[i % LETTERS.len()] as char;
+            placement
+                .cells
+                .iter()
+                .for_each(|&(r, c)| grid[r][c] = letter);
+        }
+        for row in grid {
+            writeln!(f, "{}", row.into_iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+/// Lays `counts[i]` copies of each `pieces[i]`, in any rotation or reflection, without overlap
+/// in a `width` × `height` region, `None` when they don't fit.
+///
+/// Rejects at once when the pieces cover more cells than the region, and accepts at once
+/// when each piece gets its own bounding box. Otherwise runs an exact backtracking search,
+/// exponential in the worst case: the first free cell in row-major order is either covered by
+/// a piece anchored on it, or left free while the spare cells allow it.
+///
+/// # Panics
+///
+/// Panics when `counts` doesn't have one entry per piece.
+pub fn pack(width: usize, height: usize, pieces: &[Polyomino], counts: &[usize]) -> Option<Layout> {
+    assert_eq!(pieces.len(), counts.len(), "one count per piece expected");
+    let needed = pieces
+        .iter()
+        .zip(counts)
+        .map(|(p, &count)| p.area() * count)
+        .sum::<usize>();
+    let slack = (width * height).checked_sub(needed)?;
+    if let Some(layout) = pack_in_boxes(width, height, pieces, counts) {
+        return Some(layout);
+    }
+
+    let mut anchored = vec![Vec::new(); width * height];
+    for (piece, p) in pieces.iter().enumerate() {
+        if counts[piece] == 0 {
+            continue;
+        }
+        for o in p.orientations() {
+            for top in 0..(height + 1).saturating_sub(o.height) {
+                for left in 0..(width + 1).saturating_sub(o.width) {
+                    let cells = o
+                        .cells
+                        .iter()
+                        .map(|&(r, c)| (top + r) * width + left + c)
+                        .collect::<Vec<_>>();
+                    anchored[cells[0]].push((piece, cells));
+                }
+            }
+        }
+    }
+    let mut search = Search {
+        anchored: &anchored,
+        remaining: counts.to_vec(),
+        left: counts.iter().sum(),
+        slack,
+        covered: vec![false; width * height],
+        coverable: vec![false; width * height],
+        laid: Vec::new(),
+    };
+    if !search.run(0) {
+        return None;
+    }
+    let placements = search
+        .laid
+        .iter()
+        .map(|&(anchor, k)| {
+            let (piece, cells) = &anchored[anchor][k];
+            Placement {
+                piece: *piece,
+                cells: cells.iter().map(|&i| (i / width, i % width)).collect(),
+            }
+        })
+        .collect();
+    Some(Layout {
+        width,
+        height,
+        placements,
+    })
+}
+
+/// Lays each piece in its own cell of a grid of boxes as large as the largest piece.
+fn pack_in_boxes(
+    width: usize,
+    height: usize,
+    pieces: &[Polyomino],
+    counts: &[usize],
+) -> Option<Layout> {
+    let used = || pieces.iter().zip(counts).filter(|(_, &count)| count > 0);
+    let box_width = used().map(|(p, _)| p.width).max().unwrap_or(1);
+    let box_height = used().map(|(p, _)| p.height).max().unwrap_or(1);
+    let per_row = width / box_width;
+    let boxes = per_row * (height / box_height);
+    if boxes < counts.iter().sum() {
+        return None;
+    }
+    let placements = counts
+        .iter()
+        .enumerate()
+        .flat_map(|(piece, &count)| std::iter::repeat_n(piece, count))
+        .enumerate()
+        .map(|(i, piece)| {
+            let (top, left) = (i / per_row * box_height, i % per_row * box_width);
+            Placement {
+                piece,
+                cells: pieces[piece]
+                    .cells
+                    .iter()
+                    .map(|&(r, c)| (top + r, left + c))
+                    .collect(),
+            }
+        })
+        .collect();
+    Some(Layout {
+        width,
+        height,
+        placements,
+    })
+}
+
+/// State of the backtracking search of [`pack`], cells being indexed in row-major order.
+struct Search<'a> {
+    /// Placements of the pieces anchored on each cell by their first cell, with the cells
+    /// they cover.
+    anchored: &'a [Vec<(usize, Vec<usize>)>],
+    remaining: Vec<usize>,
+    /// Sum of `remaining`.
+    left: usize,
+    /// Cells that can still be left free.
+    slack: usize,
+    covered: Vec<bool>,
+    /// Scratch space of [`Search::dead_cells`].
+    coverable: Vec<bool>,
+    /// Anchor and index in `anchored` of the laid placements.
+    laid: Vec<(usize, usize)>,
+}
+
+impl Search<'_> {
+    fn fits(&self, piece: usize, cells: &[usize]) -> bool {
+        self.remaining[piece] > 0 && cells.iter().all(|&i| !self.covered[i])
+    }
+
+    /// Number of free cells from `from` onwards that no remaining piece can cover anymore.
+    fn dead_cells(&mut self, from: usize) -> usize {
+        let anchored = self.anchored;
+        self.coverable[from..].fill(false);
+        for (piece, cells) in anchored[from..].iter().flatten() {
+            if self.fits(*piece, cells) {
+                cells.iter().for_each(|&i| self.coverable[i] = true);
+            }
+        }
+        (from..self.covered.len())
+            .filter(|&i| !self.covered[i] && !self.coverable[i])
+            .count()
+    }
+
+    /// Lays the remaining pieces on the cells from `from` onwards, every cell before it being
+    /// settled.
+    fn run(&mut self, from: usize) -> bool {
+        if self.left == 0 {
+            return true;
+        }
+        let Some(free) = (from..self.covered.len()).find(|&i| !self.covered[i]) else {
+            return false;
+        };
+        if self.dead_cells(free) > self.slack {
+            return false;
+        }
+        let anchored = self.anchored;
+        for (k, (piece, cells)) in anchored[free].iter().enumerate() {
+            if !self.fits(*piece, cells) {
+                continue;
+            }
+            cells.iter().for_each(|&i| self.covered[i] = true);
+            self.remaining[*piece] -= 1;
+            self.left -= 1;
+            self.laid.push((free, k));
+            if self.run(free + 1) {
+                return true;
+            }
+            self.laid.pop();
+            self.left += 1;
+            self.remaining[*piece] += 1;
+            cells.iter().for_each(|&i| self.covered[i] = false);
+        }
+        if self.slack > 0 {
+            self.slack -= 1;
+            if self.run(free + 1) {
+                return true;
+            }
+            self.slack += 1;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pack, Layout, Polyomino};
+
+    fn shape(s: &str) -> Polyomino {
+        s.replace(' ', "\n").parse().unwrap()
+    }
+
+    /// Checks that the layout lays `counts` pieces without overlap inside the region.
+    fn check(layout: &Layout, pieces: &[Polyomino], counts: &[usize]) {
+        let mut laid = vec![0; pieces.len()];
+        let mut covered = vec![vec![false; layout.width]; layout.height];
+        for placement in &layout.placements {
+            laid[placement.piece] += 1;
+            let p = Polyomino::new(placement.cells.iter().copied()).unwrap();
+            assert!(pieces[placement.piece].orientations().contains(&p));
+            for &(r, c) in &placement.cells {
+                assert!(!covered[r][c], "overlap at {r},{c}");
+                covered[r][c] = true;
+            }
+        }
+        assert_eq!(counts, laid);
+    }
+
+    #[test]
+    fn test_orientations() {
+        assert_eq!(1, shape("##. ##.").orientations().len());
+        assert_eq!(2, shape("###").orientations().len());
+        assert_eq!(4, shape("### .#.").orientations().len());
+        assert_eq!(8, shape("##. .## .#.").orientations().len());
+        let l = shape("#. #. ##");
+        assert_eq!((2, 3), (l.width(), l.height()));
+        assert_eq!(shape("### #.."), l.rotate());
+        assert_eq!(shape(".# .# ##"), l.reflect());
+        assert!("#x".parse::<Polyomino>().is_err());
+        assert!("..".parse::<Polyomino>().is_err());
+    }
+
+    #[test]
+    fn test_pack() {
+        let pieces = [
+            "###\n##.\n##.",
+            "###\n##.\n.##",
+            ".##\n###\n##.",
+            "##.\n###\n##.",
+            "###\n#..\n###",
+            "###\n.#.\n###",
+        ]
+        .map(|s| s.parse::<Polyomino>().unwrap());
+
+        let counts = [0, 0, 0, 0, 2, 0];
+        let layout = pack(4, 4, &pieces, &counts).unwrap();
+        check(&layout, &pieces, &counts);
+        assert_eq!(16 - 14, layout.to_string().matches('.').count());
+
+        let counts = [1, 0, 1, 0, 2, 2];
+        check(&pack(12, 5, &pieces, &counts).unwrap(), &pieces, &counts);
+        assert_eq!(None, pack(12, 5, &pieces, &[1, 0, 1, 0, 3, 2]));
+        assert_eq!(None, pack(3, 3, &pieces, &[0, 0, 0, 0, 2, 0]));
+
+        let counts = [1, 1, 1, 1, 1, 1];
+        check(&pack(18, 3, &pieces, &counts).unwrap(), &pieces, &counts);
+        let empty = pack(2, 2, &pieces, &[0; 6]).unwrap();
+        assert_eq!("..\n..\n", empty.to_string());
+    }
+
+    #[test]
+    fn test_tiling() {
+        // twelve L-trominoes exactly tile a 6x6 square
+        let l = [shape("#. ##")];
+        check(&pack(6, 6, &l, &[12]).unwrap(), &l, &[12]);
+        assert_eq!(None, pack(3, 3, &l, &[3]));
+    }
+}
@@ -0,0 +1,213 @@
+use std::env;
+use std::fmt::Display;
+use std::io::{BufRead, Cursor, Read};
+use std::path::PathBuf;
+use std::process;
+use std::str::FromStr;
+use std::time::Instant;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::answers::Answers;
+use crate::input::{day_inputs, input_path, inputs_dir, open_file, read_input, Input, InputSource};
+use crate::integrity::{Checker, Manifest};
+use crate::mem;
+
+const RESULT_WIDTH: usize = 48;
+
+/// Prints `err` and exits with a failure status.
+fn exit_with(err: anyhow::Error) -> ! {
+    eprintln!("error: {err:#}");
+    process::exit(1);
+}
+
+/// Reads the input selected on the command line (see [`InputSource::from_args`], `_1` by
+/// default) for a crate whose manifest lives in `manifest_dir`, exiting when it can't be found.
+pub fn load_input(manifest_dir: &str, day: &str) -> Input {
+    read_input(
+        manifest_dir,
+        day,
+        &InputSource::from_args().unwrap_or_default(),
+    )
+    .unwrap_or_else(|err| exit_with(err))
+}
+
+/// Value of the `--{name}=VALUE` command line option, if given, exiting when it doesn't parse.
+/// Answers aren't checked when options are given.
+pub fn cli_option<T>(name: &str) -> Option<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let prefix = format!("--{name}=");
+    let value = env::args()
+        .skip(1)
+        .find_map(|arg| arg.strip_prefix(&prefix).map(ToOwned::to_owned))?;
+    let parsed = value
+        .parse()
+        .unwrap_or_else(|err| exit_with(anyhow!("invalid --{name} {value:?}: {err}")));
+    Some(parsed)
+}
+
+/// Whether the `--{name}` command line flag is given. Unlike options, flags don't change the
+/// answers, which are still checked.
+pub fn cli_flag(name: &str) -> bool {
+    let flag = format!("--{name}");
+    env::args().skip(1).any(|arg| arg == flag)
+}
+
+/// Runs a part and formats its result, checked against `expected` when known, along with the
+/// time it took and the peak memory use of the process while it ran. Returns whether the part
+/// succeeded.
+fn run_part<R: Display>(ex: impl FnOnce() -> Result<R>, expected: Option<&str>) -> (String, bool) {
+    mem::reset_peak();
+    let start = Instant::now();
+    let res = ex();
+    let duration = start.elapsed();
+    let stats = match mem::peak_rss() {
+        Some(peak) => format!("({duration:.2?}, {})", mem::format_size(peak)),
+        None => format!("({duration:.2?})"),
+    };
+    match res {
+        Ok(value) => {
+            let value = value.to_string();
+            match expected {
+                Some(expected) if expected == value => (format!("{value} ok {stats}"), true),
+                Some(expected) => (format!("{value} != {expected} {stats}"), false),
+                None => (format!("{value} {stats}"), true),
+            }
+        }
+        Err(err) => (format!("error: {err}"), false),
+    }
+}
+
+/// Runs `run` on the input selected on the command line, or on every `inputs/{day}_*.txt`
+/// when there is none, and prints one line per input with its answers. `run` gets the input
+/// and a checker to feed it to, and returns the formatted answers of both parts, checked
+/// against the expected ones for the input hash, and whether they are right. Inputs that
+/// look corrupted or don't match their hash in `inputs.sha256` get a warning. Exits with a
+/// failure status when an answer is wrong.
+fn run_inputs<F>(manifest_dir: &str, day: &str, mut run: F)
+where
+    F: FnMut(
+        &InputSource,
+        &mut Checker,
+        &dyn Fn(&str, usize) -> Option<String>,
+    ) -> Result<[(String, bool); 2]>,
+{
+    let sources = match InputSource::from_args() {
+        Some(source) => vec![source],
+        None => day_inputs(manifest_dir, day).unwrap_or_else(|err| exit_with(err)),
+    };
+    // options change what the parts compute, so the recorded answers don't apply, unlike flags
+    let with_options = env::args()
+        .skip(1)
+        .any(|arg| arg.starts_with("--") && arg.contains('='));
+    let (answers, manifest) = match inputs_dir(manifest_dir) {
+        Ok(dir) => (
+            match with_options {
+                true => Answers::default(),
+                false => Answers::load(&dir).unwrap_or_else(|err| exit_with(err)),
+            },
+            Manifest::load(&dir).unwrap_or_else(|err| exit_with(err)),
+        ),
+        Err(_) => Default::default(),
+    };
+
+    let names = sources.iter().map(|s| s.name(day)).collect::<Vec<_>>();
+    let name_width = names.iter().map(String::len).max().unwrap_or(0).max(5);
+    println!("{:<name_width$}  {:<RESULT_WIDTH$}  ex2", "input", "ex1");
+
+    let mut all_ok = true;
+    for (source, name) in sources.iter().zip(names) {
+        let mut checker = Checker::default();
+        let expected = |hash: &str, part| answers.expected(hash, part).map(ToOwned::to_owned);
+        let [(res_ex1, ok_ex1), (res_ex2, ok_ex2)] =
+            run(source, &mut checker, &expected).unwrap_or_else(|err| exit_with(err));
+
+        let hash = checker.hash();
+        let mut warnings = checker
+            .warnings()
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>();
+        if let InputSource::Variant(_) = source {
+            warnings.extend(manifest.check(&format!("{name}.txt"), &hash));
+        }
+        for warning in warnings {
+            eprintln!("warning: {name} {warning}");
+        }
+        println!("{name:<name_width$}  {res_ex1:<RESULT_WIDTH$}  {res_ex2}");
+        all_ok &= ok_ex1 && ok_ex2;
+    }
+    if !all_ok {
+        process::exit(1);
+    }
+}
+
+/// Runs both parts of `day` on the input selected on the command line, or on every
+/// `inputs/{day}_*.txt` when there is none, and prints one line per input with each answer
+/// checked against `answers.txt`, the time it took and the peak memory use. Inputs that look
+/// corrupted or don't match their hash in `inputs.sha256` get a warning. Exits with a failure
+/// status when an answer is wrong.
+pub fn run_day<F1, F2, R1, R2>(manifest_dir: &str, day: &str, ex1: F1, ex2: F2)
+where
+    F1: Fn(&str) -> Result<R1>,
+    F2: Fn(&str) -> Result<R2>,
+    R1: Display,
+    R2: Display,
+{
+    run_inputs(manifest_dir, day, |source, checker, expected| {
+        let file = read_input(manifest_dir, day, source)?;
+        checker.update(file.as_bytes());
+        let hash = checker.hash();
+        Ok([
+            run_part(|| ex1(&file), expected(&hash, 1).as_deref()),
+            run_part(|| ex2(&file), expected(&hash, 2).as_deref()),
+        ])
+    });
+}
+
+/// Same as [`run_day`] for days whose parts read their input as a stream, e.g. line by line,
+/// so that inputs far larger than memory can be run. Each part reads the input file again;
+/// only stdin and encrypted inputs are held in memory.
+pub fn run_day_streaming<F1, F2, R1, R2>(manifest_dir: &str, day: &str, ex1: F1, ex2: F2)
+where
+    F1: Fn(&mut dyn BufRead) -> Result<R1>,
+    F2: Fn(&mut dyn BufRead) -> Result<R2>,
+    R1: Display,
+    R2: Display,
+{
+    enum Stream {
+        File(PathBuf),
+        Memory(Vec<u8>),
+    }
+    impl Stream {
+        fn open(&self) -> Result<Box<dyn BufRead + '_>> {
+            match self {
+                Stream::File(path) => open_file(path),
+                Stream::Memory(data) => Ok(Box::new(Cursor::new(data))),
+            }
+        }
+    }
+
+    run_inputs(manifest_dir, day, |source, checker, expected| {
+        let stream = match input_path(manifest_dir, day, source)? {
+            Some(path) => Stream::File(path),
+            None => {
+                let mut data = Vec::new();
+                std::io::stdin()
+                    .read_to_end(&mut data)
+                    .context("failed to read input from stdin")?;
+                Stream::Memory(data)
+            }
+        };
+        checker.update_from(stream.open()?)?;
+        let hash = checker.hash();
+        let mut input1 = stream.open()?;
+        let res_ex1 = run_part(|| ex1(&mut input1), expected(&hash, 1).as_deref());
+        let mut input2 = stream.open()?;
+        let res_ex2 = run_part(|| ex2(&mut input2), expected(&hash, 2).as_deref());
+        Ok([res_ex1, res_ex2])
+    });
+}
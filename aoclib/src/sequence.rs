@@ -0,0 +1,240 @@
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Zero};
+
+/// Leading terms of the successive finite differences of `terms`: `Δ^j terms[0]` for each `j`,
+/// without the trailing zeros.
+fn leading_differences(terms: &[i64]) -> Vec<BigInt> {
+    let mut row = terms.iter().map(|&t| BigInt::from(t)).collect::<Vec<_>>();
+    let mut leading = Vec::with_capacity(row.len());
+    while let Some(first) = row.first() {
+        leading.push(first.clone());
+        row = row.windows(2).map(|w| &w[1] - &w[0]).collect();
+    }
+    while leading.last().is_some_and(Zero::is_zero) {
+        leading.pop();
+    }
+    leading
+}
+
+/// Degree of the polynomial generating `terms`: the smallest `d` whose `d + 1`-th finite
+/// differences are all zero. `None` when there are too few terms to see it, i.e. when only
+/// the last difference, of a single term, vanishes, if any.
+pub fn detect_degree(terms: &[i64]) -> Option<usize> {
+    let mut row = terms.iter().map(|&t| BigInt::from(t)).collect::<Vec<_>>();
+    let mut degree = 0;
+    while row.len() > 1 {
+        let next = row.windows(2).map(|w| &w[1] - &w[0]).collect::<Vec<_>>();
+        if next.iter().all(Zero::is_zero) {
+            return Some(degree);
+        }
+        row = next;
+        degree += 1;
+    }
+    None
+}
+
+/// Term of index `k` of the lowest-degree polynomial sequence starting with `terms`, `k`
+/// being negative for terms before the first one. `None` when `terms` is empty.
+///
+/// Newton's forward formula: the sum of `Δ^j terms[0] * binomial(k, j)`, all integers, so the
+/// result is exact even for indices like 26501365.
+pub fn kth_term(terms: &[i64], k: i64) -> Option<BigInt> {
+    if terms.is_empty() {
+        return None;
+    }
+    let k = BigInt::from(k);
+    let mut binomial = BigInt::one();
+    let mut term = BigInt::zero();
+    for (j, difference) in leading_differences(terms).iter().enumerate() {
+        term += difference * &binomial;
+        // binomial(k, j + 1) from binomial(k, j), the division being exact
+        binomial = binomial * (&k - j) / (j + 1);
+    }
+    Some(term)
+}
+
+/// Same as [`kth_term`] in `i128`, without allocating big integers: `None` when `terms` is
+/// empty or an intermediate value overflows, in which case [`kth_term`] still has the answer.
+pub fn kth_term_i128(terms: &[i64], k: i64) -> Option<i128> {
+    let mut row = terms.iter().map(|&t| i128::from(t)).collect::<Vec<_>>();
+    let k = i128::from(k);
+    let mut binomial = 1i128;
+    let mut term = 0i128;
+    let mut j = 0;
+    // the rows of differences are computed in place, each one shorter than the previous one
+    while !row.is_empty() && row.iter().any(|&d| d != 0) {
+        term = term.checked_add(row[0].checked_mul(binomial)?)?;
+        binomial = binomial.checked_mul(k - j)? / (j + 1);
+        for i in 0..row.len() - 1 {
+            row[i] = row[i + 1].checked_sub(row[i])?;
+        }
+        row.pop();
+        j += 1;
+    }
+    (!terms.is_empty()).then_some(term)
+}
+
+/// Term following `terms`.
+pub fn next_term(terms: &[i64]) -> Option<BigInt> {
+    kth_term(terms, terms.len() as i64)
+}
+
+/// Term preceding `terms`.
+pub fn previous_term(terms: &[i64]) -> Option<BigInt> {
+    kth_term(terms, -1)
+}
+
+/// Polynomial with exact rational coefficients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    /// Coefficient of each power, lowest first, without trailing zeros.
+    coefficients: Vec<BigRational>,
+}
+
+impl Polynomial {
+    /// Polynomial of lowest degree going through `points` (Lagrange interpolation, by divided
+    /// differences), `None` when two points have the same `x` or there are none.
+    pub fn interpolate(points: &[(i64, i64)]) -> Option<Self> {
+        let rational = |v: i64| BigRational::from_integer(v.into());
+        let xs = points.iter().map(|&(x, _)| rational(x)).collect::<Vec<_>>();
+        let mut divided = points.iter().map(|&(_, y)| rational(y)).collect::<Vec<_>>();
+        for j in 1..points.len() {
+            for i in (j..points.len()).rev() {
+                let dx = &xs[i] - &xs[i - j];
+                if dx.is_zero() {
+                    return None;
+                }
+                divided[i] = (&divided[i] - &divided[i - 1]) / dx;
+            }
+        }
+        // expand the Newton form c0 + (x - x0) (c1 + (x - x1) (c2 + ...)) from the inside
+        let mut coefficients = vec![divided.pop()?];
+        for (c, x) in divided.into_iter().zip(&xs).rev() {
+            // multiply by (x - xi), then add ci
+            coefficients.insert(0, BigRational::zero());
+            for k in 0..coefficients.len() - 1 {
+                let shifted = &coefficients[k + 1] * x;
+                coefficients[k] -= shifted;
+            }
+            coefficients[0] += c;
+        }
+        while coefficients.len() > 1 && coefficients.last().is_some_and(Zero::is_zero) {
+            coefficients.pop();
+        }
+        Some(Self { coefficients })
+    }
+
+    /// Polynomial of lowest degree whose value at `i` is `terms[i]`.
+    pub fn fit(terms: &[i64]) -> Option<Self> {
+        let points = terms
+            .iter()
+            .enumerate()
+            .map(|(i, &t)| (i as i64, t))
+            .collect::<Vec<_>>();
+        Self::interpolate(&points)
+    }
+
+    /// Coefficient of each power, lowest first.
+    pub fn coefficients(&self) -> &[BigRational] {
+        &self.coefficients
+    }
+
+    /// Degree, `0` for constants including zero.
+    pub fn degree(&self) -> usize {
+        self.coefficients.len() - 1
+    }
+
+    pub fn eval(&self, x: &BigRational) -> BigRational {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(BigRational::zero(), |acc, c| acc * x + c)
+    }
+
+    /// Value at the integer `x`, `None` when it isn't an integer.
+    pub fn eval_int(&self, x: i64) -> Option<BigInt> {
+        let value = self.eval(&BigRational::from_integer(x.into()));
+        value.is_integer().then(|| value.to_integer())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use num_rational::BigRational;
+    use proptest::prelude::*;
+
+    use super::{detect_degree, kth_term, kth_term_i128, next_term, previous_term, Polynomial};
+
+    fn q(n: i64, d: i64) -> BigRational {
+        BigRational::new(n.into(), d.into())
+    }
+
+    #[test]
+    fn test_sequences() {
+        let triangular = [1, 3, 6, 10, 15, 21];
+        assert_eq!(Some(2), detect_degree(&triangular));
+        assert_eq!(Some(BigInt::from(28)), next_term(&triangular));
+        assert_eq!(Some(BigInt::from(0)), previous_term(&triangular));
+        assert_eq!(Some(BigInt::from(5050)), kth_term(&triangular, 99));
+        assert_eq!(Some(0), detect_degree(&[7, 7]));
+        assert_eq!(None, detect_degree(&[1, 2]));
+        assert_eq!(None, detect_degree(&[5]));
+        assert_eq!(Some(BigInt::from(5)), next_term(&[5]));
+        assert_eq!(None, next_term(&[]));
+        assert_eq!(Some(5050), kth_term_i128(&triangular, 99));
+        assert_eq!(Some(0), kth_term_i128(&[0, 0], -1));
+        assert_eq!(None, kth_term_i128(&[], 0));
+
+        // differences doubling at each row overflow i128 after about 64 rows
+        let alternating = (0..80)
+            .map(|i| if i % 2 == 0 { i64::MAX } else { i64::MIN })
+            .collect::<Vec<_>>();
+        assert_eq!(None, kth_term_i128(&alternating, 80));
+        assert!(next_term(&alternating).is_some());
+
+        let p = Polynomial::fit(&triangular).unwrap();
+        assert_eq!(2, p.degree());
+        assert_eq!(&[q(1, 1), q(3, 2), q(1, 2)], p.coefficients());
+        assert_eq!(Some(BigInt::from(28)), p.eval_int(6));
+        assert_eq!(q(15, 8), p.eval(&q(1, 2)));
+        assert_eq!(0, Polynomial::fit(&[0, 0, 0]).unwrap().degree());
+        assert_eq!(None, Polynomial::interpolate(&[(1, 2), (1, 3)]));
+    }
+
+    #[test]
+    fn test_far_extrapolation() {
+        // a quadratic sampled every 131 steps from step 65, as in 2023 d21
+        let f = |x: i64| 3 * x * x - 7 * x + 11;
+        let steps: i64 = 26501365;
+        let samples = [0, 1, 2].map(|i| f(65 + 131 * i));
+        let k = (steps - 65) / 131;
+        let expected = BigInt::from(3) * steps * steps - 7 * steps + 11i64;
+        assert_eq!(Some(expected.clone()), kth_term(&samples, k));
+        let points = [0, 1, 2].map(|i| (65 + 131 * i, f(65 + 131 * i)));
+        let p = Polynomial::interpolate(&points).unwrap();
+        assert_eq!(&[q(11, 1), q(-7, 1), q(3, 1)], p.coefficients());
+        assert_eq!(Some(expected), p.eval_int(steps));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_polynomial_sequences(
+            coefficients in prop::collection::vec(-50..50i64, 1..5),
+            extra in 1..3usize,
+            k in -100..100i64,
+        ) {
+            let f = |x: i64| coefficients.iter().rev().fold(0, |acc, &c| acc * x + c);
+            let degree = coefficients.iter().rposition(|&c| c != 0).unwrap_or(0);
+            let terms = (0..(degree + 1 + extra) as i64).map(f).collect::<Vec<_>>();
+            prop_assert_eq!(Some(degree), detect_degree(&terms));
+            prop_assert_eq!(Some(BigInt::from(f(k))), kth_term(&terms, k));
+            prop_assert_eq!(Some(BigInt::from(f(-1))), previous_term(&terms));
+            prop_assert_eq!(Some(i128::from(f(k))), kth_term_i128(&terms, k));
+            let p = Polynomial::fit(&terms).unwrap();
+            prop_assert_eq!(degree, p.degree());
+            prop_assert_eq!(Some(BigInt::from(f(k))), p.eval_int(k));
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use num_bigint::BigUint;
+
+/// Indices of the lexicographically largest subsequence of `k` items of `items`, the earliest
+/// ones among equal subsequences, `None` when there are fewer than `k` items.
+///
+/// Keeps a stack of the chosen items, decreasing from the bottom, and pops the smaller ones
+/// as long as enough items remain to refill it: O(n).
+pub fn largest_subsequence<T: Ord>(items: &[T], k: usize) -> Option<Vec<usize>> {
+    if k > items.len() {
+        return None;
+    }
+    let mut stack: Vec<usize> = Vec::with_capacity(k);
+    for (i, item) in items.iter().enumerate() {
+        while let Some(&top) = stack.last() {
+            if items[top] >= *item || stack.len() - 1 + items.len() - i < k {
+                break;
+            }
+            stack.pop();
+        }
+        if stack.len() < k {
+            stack.push(i);
+        }
+    }
+    Some(stack)
+}
+
+/// Digits picked in order from a number, by [`largest_number`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigitSubsequence {
+    pub indices: Vec<usize>,
+    /// Values of the picked digits, most significant first.
+    pub digits: Vec<u8>,
+}
+
+impl DigitSubsequence {
+    /// Number formed by the digits, `None` when it doesn't fit.
+    pub fn to_u128(&self) -> Option<u128> {
+        self.digits.iter().try_fold(0u128, |acc, &d| {
+            acc.checked_mul(10)?.checked_add(u128::from(d))
+        })
+    }
+
+    /// Number formed by the digits, of any size.
+    pub fn to_biguint(&self) -> BigUint {
+        BigUint::from_radix_be(&self.digits, 10).unwrap_or_default()
+    }
+}
+
+/// Largest number made of `k` of `digits` (values `0..=9`) kept in their order, `None` when
+/// there are fewer than `k` digits.
+pub fn largest_number(digits: &[u8], k: usize) -> Option<DigitSubsequence> {
+    let indices = largest_subsequence(digits, k)?;
+    Some(DigitSubsequence {
+        digits: indices.iter().map(|&i| digits[i]).collect(),
+        indices,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use proptest::prelude::*;
+
+    use super::{largest_number, largest_subsequence};
+
+    fn digits(s: &str) -> Vec<u8> {
+        s.bytes().map(|b| b - b'0').collect()
+    }
+
+    #[test]
+    fn test_largest_number() {
+        let n = largest_number(&digits("818181911112111"), 12).unwrap();
+        assert_eq!(Some(888911112111), n.to_u128());
+        assert_eq!(vec![0, 2, 4, 6, 7, 8, 9, 10, 11, 12, 13, 14], n.indices);
+        let n = largest_number(&digits("811111111111119"), 2).unwrap();
+        assert_eq!((Some(89), vec![0, 14]), (n.to_u128(), n.indices));
+        assert_eq!(vec![0, 1], largest_subsequence(&[5, 5, 5], 2).unwrap());
+        assert_eq!(None, largest_subsequence(&[1], 2));
+        assert_eq!(Some(0), largest_number(&[], 0).unwrap().to_u128());
+
+        let long = digits(&"9".repeat(50));
+        let n = largest_number(&long, 45).unwrap();
+        assert_eq!(None, n.to_u128());
+        assert_eq!(BigUint::from(10u8).pow(45) - 1u8, n.to_biguint());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_largest_subsequence(items in prop::collection::vec(0..4u8, 0..10), k in 0..10usize) {
+            // every subsequence of length k, as the bitmask of its indices
+            let best = (0..1u32 << items.len())
+                .filter(|mask| mask.count_ones() as usize == k)
+                .map(|mask| (0..items.len()).filter(|i| mask >> i & 1 == 1).collect::<Vec<_>>())
+                .max_by(|a, b| {
+                    let values = |s: &[usize]| s.iter().map(|&i| items[i]).collect::<Vec<_>>();
+                    // largest values, then earliest indices
+                    values(a).cmp(&values(b)).then_with(|| b.cmp(a))
+                });
+            prop_assert_eq!(best, largest_subsequence(&items, k));
+        }
+    }
+}